@@ -0,0 +1,102 @@
+//! The original `parse_secs`, which collected each number into a `Vec<char>`
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    OutOfOrder,
+    AlreadySeen,
+    InvalidData,
+}
+
+pub fn parse_secs(input: &str) -> Result<u64, Error> {
+    #[derive(Default)]
+    struct Buf(Vec<char>);
+    impl Buf {
+        fn is_empty(&self) -> bool {
+            self.0.is_empty()
+        }
+        fn append(&mut self, ch: char) {
+            self.0.push(ch)
+        }
+        fn parse(&mut self, magnitude: Magnitude) -> Option<u64> {
+            if self.is_empty() {
+                return None;
+            }
+
+            Some(
+                self.0
+                    .drain(..)
+                    .filter_map(|c| c.to_digit(10).map(u64::from))
+                    .fold(0, |a, c| 10 * a + c)
+                    * magnitude.to_secs(),
+            )
+        }
+    }
+
+    #[derive(Default)]
+    struct Order(Option<Magnitude>);
+    impl Order {
+        fn verify(&mut self, magnitude: Magnitude) -> Result<Magnitude, Error> {
+            match self.0 {
+                Some(a) if a > magnitude => self.0.replace(magnitude),
+                Some(a) if a == magnitude => return Err(Error::AlreadySeen),
+                None => self.0.replace(magnitude),
+                _ => return Err(Error::OutOfOrder),
+            };
+            Ok(magnitude)
+        }
+    }
+
+    #[derive(Copy, Clone, PartialEq, PartialOrd)]
+    enum Magnitude {
+        Second,
+        Minute,
+        Hour,
+        Day,
+    }
+    impl Magnitude {
+        fn to_secs(self) -> u64 {
+            match self {
+                Self::Second => 1,
+                Self::Minute => 60,
+                Self::Hour => 60 * 60,
+                Self::Day => 60 * 60 * 24,
+            }
+        }
+    }
+
+    let (mut order, mut buf): (Order, Buf) = Default::default();
+    let mut iter = input.chars().peekable();
+    let mut acc = 0;
+
+    macro_rules! verify {
+        ($mag:expr) => {{
+            if buf.is_empty() {
+                return Err(Error::InvalidData);
+            }
+            match buf.parse(order.verify($mag)?) {
+                Some(d) => d,
+                None => break,
+            }
+        }};
+    }
+
+    while let Some(left) = iter.next() {
+        acc += match (left, iter.peek()) {
+            ('s', ..) => verify!(Magnitude::Second),
+            ('m', ..) => verify!(Magnitude::Minute),
+            ('h', ..) => verify!(Magnitude::Hour),
+            ('d', ..) => verify!(Magnitude::Day),
+            (c, Some(..)) if c.is_ascii_digit() => {
+                if buf.is_empty() && c == '0' {
+                    return Err(Error::InvalidData);
+                }
+                buf.append(c);
+                continue;
+            }
+            (c, None) if c.is_ascii_digit() => return Err(Error::InvalidData),
+            _ => continue,
+        }
+    }
+
+    Ok(acc)
+}
@@ -0,0 +1,72 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+
+mod baseline;
+
+/// Inputs that every parser agrees on
+const INPUTS: &[&str] = &["1s", "30m 59s", "1h 1m 1s", "3d 5m", "7d 23h 59m 59s"];
+
+fn parse_secs(c: &mut Criterion) {
+    for input in INPUTS {
+        let expected = simple_duration_parse::parse_secs(input).unwrap();
+        assert_eq!(baseline::parse_secs(input), Ok(expected));
+        assert_eq!(
+            humantime::parse_duration(input).unwrap().as_secs(),
+            expected
+        );
+    }
+
+    let mut group = c.benchmark_group("parse_secs");
+    for input in INPUTS {
+        group.bench_with_input(BenchmarkId::new("bytes", input), input, |b, input| {
+            b.iter(|| simple_duration_parse::parse_secs(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("baseline", input), input, |b, input| {
+            b.iter(|| baseline::parse_secs(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("humantime", input), input, |b, input| {
+            b.iter(|| humantime::parse_duration(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+fn log_lines(c: &mut Criterion) {
+    let lines = (0..1_000)
+        .map(|i| format!("{}h {}m {}s", i % 24 + 1, i % 59 + 1, i % 59 + 1))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("log_lines");
+    group.bench_function("bytes", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| simple_duration_parse::parse_secs(black_box(line)).unwrap())
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("baseline", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| baseline::parse_secs(black_box(line)).unwrap())
+                .sum::<u64>()
+        })
+    });
+    group.bench_function("humantime", |b| {
+        b.iter(|| {
+            lines
+                .iter()
+                .map(|line| {
+                    humantime::parse_duration(black_box(line))
+                        .unwrap()
+                        .as_secs()
+                })
+                .sum::<u64>()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, parse_secs, log_lines);
+criterion_main!(benches);
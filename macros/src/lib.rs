@@ -0,0 +1,50 @@
+//! Compile-time duration literals for [`simple_duration_parse`]
+
+use proc_macro::TokenStream;
+use quote::quote;
+use std::convert::TryFrom;
+use syn::{parse_macro_input, LitStr};
+
+/// Parse a duration literal at compile time into a `std::time::Duration`
+///
+/// This accepts the same format as [`simple_duration_parse::parse_secs`], but keeps
+/// any sub-second components. The expansion is a `const` expression.
+///
+/// ```rust
+/// use simple_duration_parse_macros::duration;
+/// use std::time::Duration;
+///
+/// const WEEK: Duration = duration!("7d");
+/// assert_eq!(WEEK, Duration::from_secs(60 * 60 * 24 * 7));
+/// assert_eq!(duration!("1h 30m 500ms"), Duration::new(5400, 500_000_000));
+/// ```
+///
+/// Invalid durations are compile errors pointing at the literal:
+///
+/// ```compile_fail
+/// use simple_duration_parse_macros::duration;
+///
+/// let _ = duration!("5m 1h"); // Out of order at 3..5 (after 0..2)
+/// ```
+#[proc_macro]
+pub fn duration(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    let value = literal.value();
+    let error = |message: String| -> TokenStream {
+        syn::Error::new(literal.span(), message)
+            .to_compile_error()
+            .into()
+    };
+
+    let nanos = match simple_duration_parse::parse_nanos(&value) {
+        Ok(nanos) => nanos,
+        Err(err) => return error(format!("invalid duration {:?}: {}", value, err)),
+    };
+    let secs = match u64::try_from(nanos / 1_000_000_000) {
+        Ok(secs) => secs,
+        Err(..) => return error(format!("duration {:?} is too large", value)),
+    };
+    let subsec = (nanos % 1_000_000_000) as u32;
+
+    quote!(::std::time::Duration::new(#secs, #subsec)).into()
+}
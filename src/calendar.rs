@@ -0,0 +1,472 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::relative::checked_add_nanos;
+use crate::{
+    check_quantity, is_month, parse, unsigned, DurationParser, Error, Magnitude, ParserOptions,
+    Span, NANOS_PER_SEC,
+};
+use std::convert::TryFrom;
+use time::{Date, OffsetDateTime};
+
+/// A span of calendar months and years, plus a fixed duration, see [`parse_calendar`]
+#[derive(Copy, Clone, Debug)]
+pub struct CalendarSpan {
+    months: u32,
+    nanos: u128,
+    calendar: Option<Span>,
+    span: Span,
+}
+
+impl CalendarSpan {
+    /// The calendar part in months, where a year is 12 months
+    pub fn months(&self) -> u32 {
+        self.months
+    }
+
+    /// The fixed part in nanoseconds
+    pub fn nanos(&self) -> u128 {
+        self.nanos
+    }
+
+    /// The span as a fixed duration, or [`Error::CalendarUnit`] at the calendar
+    /// components if there are any
+    pub fn to_duration<T: DurationParser>(&self) -> Result<T, Error> {
+        if let Some(span) = self.calendar {
+            return Err(Error::CalendarUnit { span });
+        }
+        let nanos = i128::try_from(self.nanos).map_err(|_| Error::Overflow { span: self.span })?;
+        T::from_nanos(nanos, self.span)
+    }
+
+    /// Add the span to a date or date-time, or `None` if it's outside of the supported years
+    ///
+    /// The calendar part is added first, keeping the day of the month unless
+    /// the month is shorter, in which case it's the last day of the month. So
+    /// Jan 31 plus a month is Feb 28, or Feb 29 in a leap year. The fixed part
+    /// is added after that, and for a [`Date`] only its whole days are.
+    pub fn apply_to<T: Anchor>(&self, anchor: T) -> Option<T> {
+        let date = anchor.date();
+        let months = i64::from(date.month() - 1) + i64::from(self.months);
+        let year = i32::try_from(i64::from(date.year()) + months / 12).ok()?;
+        let month = (months % 12 + 1) as u8;
+        let day = date.day().min(days_in_month(year, month));
+        let date = Date::try_from_ymd(year, month, day).ok()?;
+        anchor.with_date(date).add_nanos(self.nanos)
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if time::is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// A date or date-time that a [`CalendarSpan`] can be applied to
+pub trait Anchor: private::Sealed {}
+
+impl Anchor for Date {}
+
+impl Anchor for OffsetDateTime {}
+
+mod private {
+    use super::*;
+
+    pub trait Sealed: Sized {
+        fn date(&self) -> Date;
+        fn with_date(self, date: Date) -> Self;
+        fn add_nanos(self, nanos: u128) -> Option<Self>;
+    }
+
+    impl Sealed for Date {
+        fn date(&self) -> Date {
+            *self
+        }
+
+        fn with_date(self, date: Date) -> Self {
+            date
+        }
+
+        fn add_nanos(self, nanos: u128) -> Option<Self> {
+            // adding past the supported range panics, so check it up front
+            let max = Date::try_from_ymd(100_000, 12, 31).ok()?;
+            let days = i64::try_from(nanos / (60 * 60 * 24 * NANOS_PER_SEC)).ok()?;
+            let day = self.julian_day().checked_add(days)?;
+            match day <= max.julian_day() {
+                true => Some(Date::from_julian_day(day)),
+                false => None,
+            }
+        }
+    }
+
+    impl Sealed for OffsetDateTime {
+        fn date(&self) -> Date {
+            OffsetDateTime::date(*self)
+        }
+
+        fn with_date(self, date: Date) -> Self {
+            date.with_time(self.time()).assume_offset(self.offset())
+        }
+
+        fn add_nanos(self, nanos: u128) -> Option<Self> {
+            checked_add_nanos(self, i128::try_from(nanos).ok()?)
+        }
+    }
+}
+
+/// The calendar units, which have no fixed length
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Unit {
+    Month,
+    Year,
+}
+
+struct Component<'a> {
+    unit: Unit,
+    whole: &'a str,
+    fraction: Option<&'a str>,
+    number: Span,
+    span: Span,
+}
+
+/// Parse a span of calendar years and months followed by fixed units, e.g. `1y 2mo 3d`
+///
+/// `y` and `mo`, with the same long forms as the other units, are calendar
+/// years and months here rather than the fixed 365 days [`parse_secs`](crate::parse_secs)
+/// uses. They take whole, unsigned numbers and have to come first. The fixed
+/// units after them accept the same format as [`parse_secs_strict`](crate::parse_secs_strict).
+///
+/// ```rust
+/// use simple_duration_parse::{parse_calendar, Error, Span};
+/// use std::time::Duration;
+///
+/// let span = parse_calendar("1 month 12h").unwrap();
+/// let date = time::date!(2021-01-31).with_time(time::time!(12:00)).assume_utc();
+/// assert_eq!(
+///     span.apply_to(date).unwrap(),
+///     time::date!(2021-03-01).midnight().assume_utc(),
+/// );
+/// assert_eq!(span.apply_to(time::date!(2024-01-31)).unwrap(), time::date!(2024-02-29));
+///
+/// assert_eq!(
+///     span.to_duration::<Duration>().unwrap_err(),
+///     Error::CalendarUnit { span: Span::new(0, 7) }
+/// );
+/// assert_eq!(
+///     parse_calendar("3d 12h").unwrap().to_duration::<Duration>().unwrap(),
+///     Duration::from_secs(84 * 60 * 60)
+/// );
+/// ```
+pub fn parse_calendar(input: &str) -> Result<CalendarSpan, Error> {
+    let options = ParserOptions::new().strict(true);
+    let mut previous: Option<(Unit, Span)> = None;
+    let (mut years, mut months) = (0_u32, 0_u32);
+    let mut calendar: Option<Span> = None;
+
+    let mut tokens = Lexer::new(input);
+    while let Some(Component {
+        unit,
+        whole,
+        fraction,
+        number,
+        span,
+    }) = component(&mut tokens)?
+    {
+        check_quantity(whole, fraction, span, &options)?;
+        if fraction.is_some() {
+            return Err(Error::InvalidData { span: number });
+        }
+        match previous {
+            Some((Unit::Month, previous)) if unit == Unit::Year => {
+                return Err(Error::OutOfOrder { span, previous })
+            }
+            Some((seen, previous)) if seen == unit => {
+                return Err(Error::AlreadySeen { span, previous })
+            }
+            _ => {}
+        }
+        previous = Some((unit, span));
+        calendar = Some(Span::new(
+            calendar.map_or(span.start, |c| c.start),
+            span.end,
+        ));
+
+        let n = whole.parse::<u32>().map_err(|_| Error::Overflow { span })?;
+        match unit {
+            Unit::Year => years = n,
+            Unit::Month => months = n,
+        }
+    }
+
+    // everything after the calendar components is fixed
+    let start = calendar.map_or(0, |c| c.end);
+    let fixed = &input[start..];
+    let nanos = match fixed.trim().is_empty() {
+        true if calendar.is_some() => 0,
+        _ => parse(fixed, &options)
+            .and_then(|nanos| unsigned(nanos, fixed))
+            .map_err(|err| match err.shift(start) {
+                // a calendar unit after the fixed ones, which are all before it
+                Error::CalendarUnit { span } => {
+                    let before = &input[start..span.start];
+                    let start = start + before.len() - before.trim_start().len();
+                    let previous = Span::new(start, start + before.trim().len());
+                    Error::OutOfOrder { span, previous }
+                }
+                err => err,
+            })?,
+    };
+
+    let span = Span::new(0, input.len());
+    let months = years
+        .checked_mul(12)
+        .and_then(|years| years.checked_add(months))
+        .ok_or(Error::Overflow { span })?;
+    Ok(CalendarSpan {
+        months,
+        nanos,
+        calendar,
+        span,
+    })
+}
+
+/// Lex a calendar component, `<number>[ ]<year or month>`, advancing `tokens` past it
+///
+/// Anything else is left for the fixed part. A sign on a calendar component
+/// returns [`Error::InvalidData`].
+fn component<'a>(tokens: &mut Lexer<'a>) -> Result<Option<Component<'a>>, Error> {
+    let mut lexer = *tokens;
+    lexer.skip_whitespace();
+    let sign = lexer.next_if(|token| matches!(token.kind, TokenKind::Other('+' | '-')));
+    let (whole, fraction, number) = match lexer.next() {
+        Some(Token {
+            kind: TokenKind::Number { whole, fraction },
+            span,
+        }) => (whole, fraction, span),
+        _ => return Ok(None),
+    };
+    lexer.skip_whitespace();
+    let (unit, end) = match lexer.next() {
+        Some(Token {
+            kind: TokenKind::Word(word),
+            span,
+        }) => match Magnitude::from_name(word) {
+            Some(Magnitude::Year) => (Unit::Year, span.end),
+            _ if is_month(word) => (Unit::Month, span.end),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+
+    if let Some(sign) = sign {
+        return Err(Error::InvalidData { span: sign.span });
+    }
+    *tokens = lexer;
+    Ok(Some(Component {
+        unit,
+        whole,
+        fraction,
+        number,
+        span: Span::new(number.start, end),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::{date, time};
+
+    #[test]
+    fn parse() {
+        const HOUR: u128 = 60 * 60 * NANOS_PER_SEC;
+
+        let tests = &[
+            ("1mo", 1, 0),
+            ("2 months", 2, 0),
+            ("1y", 12, 0),
+            ("1 Year 2 Months 3 days", 14, 72 * HOUR),
+            ("1yr 1w", 12, 7 * 24 * HOUR),
+            ("1.5h", 0, 3 * HOUR / 2),
+            ("1mo1d", 1, 24 * HOUR),
+            ("1mo 12:00:00", 1, 12 * HOUR),
+            ("1y +1d", 12, 24 * HOUR),
+            (" 2 mos ", 2, 0),
+        ];
+
+        for (input, months, nanos) in tests {
+            let span = parse_calendar(input).unwrap();
+            assert_eq!(span.months(), *months, "input: {}", input);
+            assert_eq!(span.nanos(), *nanos, "input: {}", input);
+        }
+
+        let tests = &[
+            (
+                "",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "1mo 1y",
+                Error::OutOfOrder {
+                    span: Span::new(4, 6),
+                    previous: Span::new(0, 3),
+                },
+            ),
+            (
+                "3d 1mo",
+                Error::OutOfOrder {
+                    span: Span::new(3, 6),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                "1y 1mo 1y",
+                Error::OutOfOrder {
+                    span: Span::new(7, 9),
+                    previous: Span::new(3, 6),
+                },
+            ),
+            (
+                "1mo 2months",
+                Error::AlreadySeen {
+                    span: Span::new(4, 11),
+                    previous: Span::new(0, 3),
+                },
+            ),
+            (
+                "1.5mo",
+                Error::InvalidData {
+                    span: Span::new(0, 3),
+                },
+            ),
+            (
+                "01mo",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1mo, 1d",
+                Error::UnexpectedCharacter {
+                    span: Span::new(3, 4),
+                },
+            ),
+            (
+                "1 fortnight",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1mo 1:00 2mo",
+                Error::OutOfOrder {
+                    span: Span::new(9, 12),
+                    previous: Span::new(4, 8),
+                },
+            ),
+            (
+                "-1mo",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1mo -1d",
+                Error::Negative {
+                    span: Span::new(3, 7),
+                },
+            ),
+            (
+                "357913942y",
+                Error::Overflow {
+                    span: Span::new(0, 10),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_calendar(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let tests = &[
+            ("1mo", date!(2021 - 01 - 31), date!(2021 - 02 - 28)),
+            ("1mo", date!(2024 - 01 - 31), date!(2024 - 02 - 29)),
+            ("1mo", date!(2021 - 03 - 31), date!(2021 - 04 - 30)),
+            ("1mo", date!(2021 - 12 - 15), date!(2022 - 01 - 15)),
+            ("13mo", date!(2021 - 12 - 15), date!(2023 - 01 - 15)),
+            ("1y", date!(2024 - 02 - 29), date!(2025 - 02 - 28)),
+            ("4y", date!(2024 - 02 - 29), date!(2028 - 02 - 29)),
+            ("1y 2mo 3d", date!(2020 - 01 - 15), date!(2021 - 03 - 18)),
+            ("1mo 1d", date!(2021 - 01 - 31), date!(2021 - 03 - 01)),
+        ];
+
+        for (input, anchor, expected) in tests {
+            let span = parse_calendar(input).unwrap();
+            assert_eq!(span.apply_to(*anchor), Some(*expected), "input: {}", input);
+            assert_eq!(
+                span.apply_to(anchor.with_time(time!(06:30)).assume_utc()),
+                Some(expected.with_time(time!(06:30)).assume_utc()),
+                "input: {}",
+                input
+            );
+        }
+
+        let span = parse_calendar("1d 23h 59m").unwrap();
+        assert_eq!(
+            span.apply_to(date!(2021 - 01 - 01)),
+            Some(date!(2021 - 01 - 02))
+        );
+
+        let span = parse_calendar("1mo 12h").unwrap();
+        let anchor = date!(2021 - 01 - 31)
+            .with_time(time!(18:00))
+            .assume_offset(time::offset!(+2));
+        assert_eq!(
+            span.apply_to(anchor).unwrap(),
+            date!(2021 - 03 - 01)
+                .with_time(time!(06:00))
+                .assume_offset(time::offset!(+2))
+        );
+
+        let span = parse_calendar("1y").unwrap();
+        assert_eq!(span.apply_to(date!(+100_000 - 01 - 01)), None);
+        assert_eq!(
+            span.apply_to(date!(+100_000 - 01 - 01).midnight().assume_utc()),
+            None
+        );
+        let span = parse_calendar("1w").unwrap();
+        assert_eq!(span.apply_to(date!(+100_000 - 12 - 30)), None);
+    }
+
+    #[test]
+    fn to_duration() {
+        use std::time::Duration;
+
+        assert_eq!(
+            parse_calendar("1y 1mo 1d")
+                .unwrap()
+                .to_duration::<Duration>()
+                .unwrap_err(),
+            Error::CalendarUnit {
+                span: Span::new(0, 6),
+            }
+        );
+        assert_eq!(
+            parse_calendar("1d 1h")
+                .unwrap()
+                .to_duration::<time::Duration>()
+                .unwrap(),
+            time::Duration::hours(25)
+        );
+    }
+}
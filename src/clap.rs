@@ -0,0 +1,226 @@
+use crate::{DurationParser, Magnitude, ParserOptions};
+use ::clap::builder::{PossibleValue, TypedValueParser};
+use ::clap::error::ErrorKind;
+use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::marker::PhantomData;
+
+/// A clap value parser for any [`DurationParser`], e.g. `--timeout 5m`
+///
+/// The accepted units are listed as the argument's possible values, so they
+/// show up in `--help`. Invalid values are reported with the offending part of
+/// the input highlighted, followed by the accepted units.
+///
+/// ```rust
+/// use clap::{Arg, Command};
+/// use simple_duration_parse::DurationValueParser;
+/// use std::time::Duration;
+///
+/// let cmd = Command::new("app").arg(
+///     Arg::new("timeout")
+///         .long("timeout")
+///         .value_parser(DurationValueParser::<Duration>::new()),
+/// );
+///
+/// let matches = cmd.try_get_matches_from(["app", "--timeout", "1m 30s"]).unwrap();
+/// assert_eq!(matches.get_one::<Duration>("timeout"), Some(&Duration::from_secs(90)));
+/// ```
+pub struct DurationValueParser<T> {
+    options: ParserOptions,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> DurationValueParser<T> {
+    /// Parse values [strictly](ParserOptions::strict), so a typo is an error rather than ignored
+    pub const fn new() -> Self {
+        Self::with_options(ParserOptions::new().strict(true))
+    }
+
+    /// Parse values with these options rather than the defaults
+    pub const fn with_options(options: ParserOptions) -> Self {
+        Self {
+            options,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for DurationValueParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Clone for DurationValueParser<T> {
+    fn clone(&self) -> Self {
+        Self::with_options(self.options)
+    }
+}
+
+impl<T> TypedValueParser for DurationValueParser<T>
+where
+    T: DurationParser + Clone + Send + Sync + 'static,
+{
+    type Value = T;
+
+    fn parse_ref(
+        &self,
+        cmd: &::clap::Command,
+        arg: Option<&::clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, ::clap::Error> {
+        let arg = arg.map_or_else(|| "...".to_string(), ToString::to_string);
+        let input = value.to_str().ok_or_else(|| {
+            ::clap::Error::raw(
+                ErrorKind::InvalidUtf8,
+                format!("invalid UTF-8 in the value for '{}'\n", arg),
+            )
+            .with_cmd(cmd)
+        })?;
+
+        self.options.parse(input).map_err(|err| {
+            let span = err.span();
+            let mut message = format!(
+                "invalid value '{}' for '{}': {}\n\n  {}\n  {}{}\n\n",
+                input,
+                arg,
+                err,
+                input,
+                " ".repeat(input[..span.start].chars().count()),
+                "^".repeat(input[span.start..span.end].chars().count().max(1)),
+            );
+            message.push_str("accepted units:");
+            for (i, magnitude) in Magnitude::DESCENDING.iter().rev().enumerate() {
+                let sep = if i == 0 { " " } else { ", " };
+                write!(message, "{}{}", sep, magnitude.suffix()).unwrap();
+            }
+            message.push('\n');
+            ::clap::Error::raw(ErrorKind::ValueValidation, message).with_cmd(cmd)
+        })
+    }
+
+    /// The accepted units, smallest first, for the help output
+    ///
+    /// These aren't values on their own, a value is any number of them with quantities
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let units = Magnitude::DESCENDING.iter().rev().map(|&magnitude| {
+            PossibleValue::new(magnitude.suffix()).help(match magnitude {
+                Magnitude::Nanosecond => "nanoseconds",
+                Magnitude::Microsecond => "microseconds",
+                Magnitude::Millisecond => "milliseconds",
+                Magnitude::Second => "seconds",
+                Magnitude::Minute => "minutes",
+                Magnitude::Hour => "hours",
+                Magnitude::Day => "days",
+                Magnitude::Week => "weeks",
+                Magnitude::Year => "years",
+            })
+        });
+        Some(Box::new(units))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::clap::{Arg, Command};
+    use std::time::Duration;
+
+    fn command() -> Command {
+        Command::new("app").arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .value_parser(DurationValueParser::<Duration>::new()),
+        )
+    }
+
+    #[test]
+    fn parse() {
+        let tests = &[
+            ("5m", Duration::from_secs(300)),
+            ("1h 30m", Duration::from_secs(5400)),
+            ("1.5s", Duration::from_millis(1500)),
+        ];
+
+        for (input, expected) in tests {
+            let matches = command()
+                .try_get_matches_from(["app", "--timeout", input])
+                .unwrap();
+            assert_eq!(
+                matches.get_one::<Duration>("timeout"),
+                Some(expected),
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn parse_time() {
+        let cmd = Command::new("app").arg(
+            Arg::new("offset")
+                .long("offset")
+                .value_parser(DurationValueParser::<time::Duration>::new()),
+        );
+        let matches = cmd
+            .try_get_matches_from(["app", "--offset=-1h 30m"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<time::Duration>("offset"),
+            Some(&time::Duration::minutes(-90))
+        );
+    }
+
+    #[test]
+    fn help() {
+        let help = command().render_help().to_string();
+        assert!(
+            help.contains("[possible values: ns, us, ms, s, m, h, d, w, y]"),
+            "{}",
+            help
+        );
+
+        let help = command().render_long_help().to_string();
+        assert!(help.contains("- ms: milliseconds\n"), "{}", help);
+    }
+
+    #[test]
+    fn error() {
+        let err = command()
+            .try_get_matches_from(["app", "--timeout", "1m 1h"])
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ValueValidation);
+        assert_eq!(
+            err.to_string(),
+            "error: invalid value '1m 1h' for '--timeout <timeout>': \
+             Out of order at 3..5 (after 0..2)\n\
+             \n  1m 1h\
+             \n     ^^\
+             \n\naccepted units: ns, us, ms, s, m, h, d, w, y\n"
+        );
+
+        let err = command()
+            .try_get_matches_from(["app", "--timeout=-5m"])
+            .unwrap_err();
+        assert!(err.to_string().contains("\n  -5m\n  ^^^\n"), "{}", err);
+
+        for input in &["foobar", ""] {
+            let err = command()
+                .try_get_matches_from(["app", "--timeout", input])
+                .unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ValueValidation, "input: {}", input);
+        }
+
+        let cmd = Command::new("app").arg(Arg::new("timeout").long("timeout").value_parser(
+            DurationValueParser::<Duration>::with_options(ParserOptions::new().allow_zero(true)),
+        ));
+        let matches = cmd
+            .try_get_matches_from(["app", "--timeout", "0s"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<Duration>("timeout"),
+            Some(&Duration::from_secs(0))
+        );
+    }
+}
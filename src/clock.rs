@@ -0,0 +1,295 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::{check_quantity, push_digit, scale, Error, Magnitude, Order, Span};
+
+#[derive(Copy, Clone, Default)]
+struct Field<'a> {
+    whole: &'a str,
+    fraction: Option<&'a str>,
+    span: Span,
+}
+
+/// Parse a clock-style component, `[d.]HH:MM:SS[.f]` or `MM:SS[.f]`, starting at `first`
+///
+/// Returns the nanoseconds and the span of the whole clock
+pub(crate) const fn parse_clock<'a>(
+    first: Token<'a>,
+    tokens: &mut Lexer<'a>,
+    order: &mut Order<'_>,
+) -> Result<(u128, Span), Error> {
+    let (whole, fraction) = match first.kind {
+        TokenKind::Number { whole, fraction } => (whole, fraction),
+        _ => panic!("clocks start with a number"),
+    };
+
+    // `2.03:00:00` is days, then hours
+    let (days, head) = match fraction {
+        Some(hours) => {
+            let dot = first.span.start + whole.len();
+            let days = Field {
+                whole,
+                fraction: None,
+                span: Span::new(first.span.start, dot),
+            };
+            let hours = Field {
+                whole: hours,
+                fraction: None,
+                span: Span::new(dot + 1, first.span.end),
+            };
+            (Some(days), hours)
+        }
+        None => (
+            None,
+            Field {
+                whole,
+                fraction: None,
+                span: first.span,
+            },
+        ),
+    };
+
+    let mut fields = [head; 3];
+    let mut len = 1;
+    let mut end = first.span.end;
+    while matches!(tokens.peek_byte(), Some(b':')) {
+        let colon = Span::new(end, end + 1);
+        tokens.next_token();
+
+        let field = match tokens.next_token() {
+            Some(Token {
+                kind: TokenKind::Number { whole, fraction },
+                span,
+            }) if span.start == colon.end => Field {
+                whole,
+                fraction,
+                span,
+            },
+            _ => return Err(Error::InvalidData { span: colon }),
+        };
+        if len == fields.len() {
+            return Err(Error::InvalidData {
+                span: Span::new(colon.start, field.span.end),
+            });
+        }
+        fields[len] = field;
+        len += 1;
+        end = field.span.end;
+    }
+
+    let clock = Span::new(first.span.start, end);
+    let magnitudes: &[Magnitude] = match (days, len) {
+        (_, 3) => &[Magnitude::Hour, Magnitude::Minute, Magnitude::Second],
+        (None, 2) => &[Magnitude::Minute, Magnitude::Second],
+        _ => return Err(Error::InvalidData { span: clock }),
+    };
+
+    let mut acc: u128 = 0;
+    if let Some(days) = days {
+        try_const!(check_quantity(days.whole, None, days.span, order.options));
+        try_const!(order.verify(Magnitude::Day, days.span));
+        acc = try_const!(scale(days.whole, "", Magnitude::Day, days.span));
+    }
+
+    let mut i = 0;
+    while i < len {
+        let (field, magnitude) = (fields[i], magnitudes[i]);
+        let leading = i == 0 && days.is_none();
+        if !leading && field.whole.len() != 2 {
+            return Err(Error::InvalidData { span: field.span });
+        }
+        let fraction = match field.fraction {
+            Some(fraction) if fraction.is_empty() => {
+                return Err(Error::InvalidData { span: field.span })
+            }
+            Some(..) if i + 1 != len => return Err(Error::InvalidData { span: field.span }),
+            Some(fraction) => fraction,
+            None => "",
+        };
+
+        // a field only wraps around when something larger came before it
+        let bounded = !order.is_empty();
+        try_const!(order.verify(magnitude, field.span));
+        let limit = match magnitude {
+            Magnitude::Hour => 24,
+            _ => 60,
+        };
+        if bounded && !below(field.whole, limit) {
+            return Err(Error::OutOfRange { span: field.span });
+        }
+
+        let nanos = try_const!(scale(field.whole, fraction, magnitude, field.span));
+        acc = match acc.checked_add(nanos) {
+            Some(acc) => acc,
+            None => return Err(Error::Overflow { span: clock }),
+        };
+        i += 1;
+    }
+
+    if acc == 0 && !order.options.allow_zero {
+        return Err(Error::InvalidData { span: clock });
+    }
+    Ok((acc, clock))
+}
+
+/// Whether the ASCII `digits` are a number less than `limit`
+const fn below(digits: &str, limit: u128) -> bool {
+    let digits = digits.as_bytes();
+    let (mut n, mut i) = (0, 0);
+    while i < digits.len() {
+        n = match push_digit(n, digits[i]) {
+            Some(n) if n < limit => n,
+            _ => return false,
+        };
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_nanos, parse_secs, parse_secs_strict, Error, Span};
+
+    #[test]
+    fn clock() {
+        let tests = &[
+            ("1:02:03", (60 * 60) + (2 * 60) + 3),
+            ("45:10", (45 * 60) + 10),
+            ("90:00", 90 * 60),
+            ("00:30", 30),
+            ("01:00:00", 60 * 60),
+            ("36:00:00", 36 * 60 * 60),
+            ("2d 03:00:00", (2 * 24 * 60 * 60) + (3 * 60 * 60)),
+            ("2.03:00:00", (2 * 24 * 60 * 60) + (3 * 60 * 60)),
+            ("1h 02:03", (60 * 60) + (2 * 60) + 3),
+            ("1:02:03 500ms", (60 * 60) + (2 * 60) + 3),
+            ("1:02:03.5", (60 * 60) + (2 * 60) + 3),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_secs(input).unwrap(), *expected, "input: {}", input);
+            assert_eq!(
+                parse_secs_strict(input).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        assert_eq!(parse_nanos("0:01.25").unwrap(), 1_250_000_000);
+
+        let tests = &[
+            (
+                "1:60",
+                Error::OutOfRange {
+                    span: Span::new(2, 4),
+                },
+            ),
+            (
+                "1:02:60",
+                Error::OutOfRange {
+                    span: Span::new(5, 7),
+                },
+            ),
+            (
+                "1:60:00",
+                Error::OutOfRange {
+                    span: Span::new(2, 4),
+                },
+            ),
+            (
+                "1d 24:00:00",
+                Error::OutOfRange {
+                    span: Span::new(3, 5),
+                },
+            ),
+            (
+                "1.24:00:00",
+                Error::OutOfRange {
+                    span: Span::new(2, 4),
+                },
+            ),
+            (
+                "1h 60:00",
+                Error::OutOfRange {
+                    span: Span::new(3, 5),
+                },
+            ),
+            (
+                "1:2",
+                Error::InvalidData {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "1:02:03:04",
+                Error::InvalidData {
+                    span: Span::new(7, 10),
+                },
+            ),
+            (
+                "1:",
+                Error::InvalidData {
+                    span: Span::new(1, 2),
+                },
+            ),
+            (
+                "1: 02",
+                Error::InvalidData {
+                    span: Span::new(1, 2),
+                },
+            ),
+            (
+                "1.02:03",
+                Error::InvalidData {
+                    span: Span::new(0, 7),
+                },
+            ),
+            (
+                "1.5:02:03",
+                Error::InvalidData {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "1:02.5:03",
+                Error::InvalidData {
+                    span: Span::new(2, 6),
+                },
+            ),
+            (
+                "0:00",
+                Error::InvalidData {
+                    span: Span::new(0, 4),
+                },
+            ),
+            (
+                "0.01:00:00",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1:00 2h",
+                Error::OutOfOrder {
+                    span: Span::new(5, 7),
+                    previous: Span::new(2, 4),
+                },
+            ),
+            (
+                "1s 1:00",
+                Error::OutOfOrder {
+                    span: Span::new(3, 4),
+                    previous: Span::new(0, 2),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_secs(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+}
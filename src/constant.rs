@@ -0,0 +1,139 @@
+use crate::{Error, ParserOptions};
+
+/// Parse the input string into seconds, in a `const` context
+///
+/// This is [`parse_secs`](crate::parse_secs) as a `const fn`, they share the
+/// same parser and accept the same input.
+///
+/// ```rust
+/// use simple_duration_parse::{parse_secs_const, Error, Span};
+/// use std::time::Duration;
+///
+/// const TIMEOUT: Duration = match parse_secs_const("1h 30m") {
+///     Ok(secs) => Duration::from_secs(secs),
+///     Err(..) => panic!("invalid timeout"),
+/// };
+/// assert_eq!(TIMEOUT, Duration::from_secs(5400));
+///
+/// const ERROR: Result<u64, Error> = parse_secs_const("5m 1h");
+/// assert_eq!(
+///     ERROR.unwrap_err(),
+///     Error::OutOfOrder { span: Span::new(3, 5), previous: Span::new(0, 2) }
+/// );
+/// ```
+pub const fn parse_secs_const(input: &str) -> Result<u64, Error> {
+    ParserOptions::new().parse_secs(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_secs, Span};
+
+    #[test]
+    fn matches_parse_secs() {
+        let tests = &[
+            "1s",
+            "1h 1m 1s",
+            "30m 59s",
+            "3d 5m",
+            "1s foobar",
+            "1s 500ms",
+            "1y 1d",
+            "1.5h",
+            "0.25d",
+            "3 hours 5 minutes",
+            "1 Day 4 HRS",
+            "3µs 1ns 2s",
+            "3μs",
+            "-5m",
+            "-1h +30m",
+            "1h -30m",
+            "+1h, 5m",
+            "1h30m",
+            "é 1h",
+            "éd",
+            "1\u{a0}s",
+            "1\u{3000}h 2\x0Bm",
+            "1dé",
+            "213503982334601d 7h 15s",
+            "213503982334601d 7h 16s",
+            "340282366920938463463374607431768211455ns",
+            "340282366920938463463374607431768211456ns",
+            "",
+            "1",
+            "1.",
+            "1.5.s",
+            "s",
+            "1 foo",
+            "1sfoo",
+            "0s",
+            "06s",
+            "0.5s",
+            "1m 1m",
+            "1s 1m",
+            "1.0000000001s",
+            "1:30",
+            "1s 1:30",
+            "1h 02:03",
+            "2.03:00:00",
+            "1:60",
+            "3mo",
+            "1y1mo",
+            "1 month",
+        ];
+
+        for input in tests {
+            assert_eq!(
+                parse_secs_const(input),
+                parse_secs(input),
+                "input: {}",
+                input
+            );
+        }
+
+        // every combination of a few fragments, including the awkward ones
+        let fragments = &[
+            "1", "05", "1.5", "30", "s", "m", "h", "mo", "ms", " ", "\u{a0}", "é", "µs", ":", ".",
+            "-", "+", "x",
+        ];
+        for a in fragments {
+            for b in fragments {
+                for c in fragments {
+                    let input = format!("{}{}{}", a, b, c);
+                    assert_eq!(
+                        parse_secs_const(&input),
+                        parse_secs(&input),
+                        "input: {}",
+                        input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn clock() {
+        const CLOCK: Result<u64, Error> = parse_secs_const("1h 02:03");
+        assert_eq!(CLOCK, Ok(3723));
+
+        const OUT_OF_RANGE: Result<u64, Error> = parse_secs_const("1:60");
+        assert_eq!(
+            OUT_OF_RANGE,
+            Err(Error::OutOfRange {
+                span: Span::new(2, 4),
+            })
+        );
+    }
+
+    #[test]
+    fn calendar_unit() {
+        const MONTHS: Result<u64, Error> = parse_secs_const("1y1mo");
+        assert_eq!(
+            MONTHS,
+            Err(Error::CalendarUnit {
+                span: Span::new(2, 5),
+            })
+        );
+    }
+}
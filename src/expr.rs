@@ -0,0 +1,529 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::{parse, Error, ParserOptions, Span};
+
+/// Parse an arithmetic expression over durations into signed nanoseconds
+///
+/// Durations, in the same format as [`parse_signed_nanos`](crate::parse_signed_nanos),
+/// can be added and subtracted, multiplied by or divided by numbers, and
+/// grouped with parentheses. `x` and `×` multiply too, and dividing a duration
+/// by another gives a number. Everything is exact: overflow returns
+/// [`Error::Overflow`], and a result finer than a nanosecond returns
+/// [`Error::Precision`], both at the span of the operation. Parentheses and
+/// signs nest at most 64 deep, past that is [`Error::InvalidData`].
+///
+/// ```rust
+/// use simple_duration_parse::{parse_expression_nanos, DurationParser as _, Error, Span};
+/// use std::time::Duration;
+///
+/// const MIN: i128 = 60 * 1_000_000_000;
+///
+/// let tests = &[
+///     ("1h - 5m", 55 * MIN),
+///     ("2 * 30m", 60 * MIN),
+///     ("3 x 20m", 60 * MIN),
+///     ("(1h + 15m) / 3", 25 * MIN),
+///     ("1d - 1h", 23 * 60 * MIN),
+///     ("1.5 * 1h 10m", 105 * MIN),
+///     ("-(1h / 30m) * 5m", -10 * MIN),
+/// ];
+///
+/// for (input, expected) in tests {
+///     assert_eq!(parse_expression_nanos(input).unwrap(), *expected);
+/// }
+///
+/// assert_eq!(Duration::parse_expression("1d - 1h").unwrap(), Duration::from_secs(23 * 60 * 60));
+/// assert_eq!(
+///     parse_expression_nanos("1h * 2h").unwrap_err(),
+///     Error::InvalidData { span: Span::new(0, 7) }
+/// );
+/// ```
+pub fn parse_expression_nanos(input: &str) -> Result<i128, Error> {
+    let mut expression = Expression {
+        input,
+        tokens: Lexer::new(input),
+        depth: 0,
+    };
+    let value = expression.sum(Span::new(0, input.len()))?;
+    if let Some(token) = expression.bump() {
+        return Err(Error::UnexpectedCharacter { span: token.span });
+    }
+    match value.kind {
+        Kind::Duration(nanos) => Ok(nanos),
+        Kind::Number(..) => Err(Error::InvalidData { span: value.span }),
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Open,
+    Close,
+}
+
+impl Operator {
+    fn from_token(token: Token<'_>) -> Option<Self> {
+        let op = match token.kind {
+            TokenKind::Other('+') => Self::Add,
+            TokenKind::Other('-') => Self::Sub,
+            TokenKind::Other('*') | TokenKind::Other('×') => Self::Mul,
+            TokenKind::Word(word) if word.eq_ignore_ascii_case("x") => Self::Mul,
+            TokenKind::Other('/') => Self::Div,
+            TokenKind::Other('(') => Self::Open,
+            TokenKind::Other(')') => Self::Close,
+            _ => return None,
+        };
+        Some(op)
+    }
+}
+
+/// An exact number, kept as a reduced fraction with a positive denominator
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Ratio {
+    num: i128,
+    den: i128,
+}
+
+impl Ratio {
+    fn new(num: i128, den: i128) -> Option<Self> {
+        let (num, den) = match den < 0 {
+            true => (num.checked_neg()?, den.checked_neg()?),
+            false => (num, den),
+        };
+        let gcd = gcd(num.unsigned_abs(), den.unsigned_abs()).max(1) as i128;
+        Some(Self {
+            num: num / gcd,
+            den: den / gcd,
+        })
+    }
+
+    fn add(self, other: Self) -> Option<Self> {
+        let num = self
+            .num
+            .checked_mul(other.den)?
+            .checked_add(other.num.checked_mul(self.den)?)?;
+        Self::new(num, self.den.checked_mul(other.den)?)
+    }
+
+    fn neg(self) -> Option<Self> {
+        Self::new(self.num.checked_neg()?, self.den)
+    }
+
+    fn mul(self, other: Self) -> Option<Self> {
+        Self::new(
+            self.num.checked_mul(other.num)?,
+            self.den.checked_mul(other.den)?,
+        )
+    }
+
+    fn recip(self) -> Option<Self> {
+        Self::new(self.den, self.num)
+    }
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Kind {
+    Duration(i128),
+    Number(Ratio),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Value {
+    kind: Kind,
+    span: Span,
+}
+
+/// How deeply parentheses and signs can nest, so that input can't overflow the stack
+const MAX_DEPTH: usize = 64;
+
+struct Expression<'a> {
+    input: &'a str,
+    tokens: Lexer<'a>,
+    /// The open parentheses and signs around the current factor
+    depth: usize,
+}
+
+impl<'a> Expression<'a> {
+    /// The next token that isn't whitespace
+    fn peek(&self) -> Option<Token<'a>> {
+        let mut tokens = self.tokens;
+        tokens.next_if(|token| token.kind == TokenKind::Whitespace);
+        tokens.next()
+    }
+
+    fn bump(&mut self) -> Option<Token<'a>> {
+        self.tokens
+            .next_if(|token| token.kind == TokenKind::Whitespace);
+        self.tokens.next()
+    }
+
+    fn operator(&self) -> Option<(Operator, Span)> {
+        let token = self.peek()?;
+        Operator::from_token(token).map(|op| (op, token.span))
+    }
+
+    /// `product (('+' | '-') product)*`, where `after` is reported if the first operand is missing
+    fn sum(&mut self, after: Span) -> Result<Value, Error> {
+        let mut left = self.product(after)?;
+        while let Some((op @ Operator::Add, span)) | Some((op @ Operator::Sub, span)) =
+            self.operator()
+        {
+            self.bump();
+            let right = self.product(span)?;
+            left = apply(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    /// `factor (('*' | 'x' | '/') factor)*`
+    fn product(&mut self, after: Span) -> Result<Value, Error> {
+        let mut left = self.factor(after)?;
+        while let Some((op @ Operator::Mul, span)) | Some((op @ Operator::Div, span)) =
+            self.operator()
+        {
+            self.bump();
+            let right = self.factor(span)?;
+            left = apply(op, left, right)?;
+        }
+        Ok(left)
+    }
+
+    /// `'(' sum ')'`, a signed factor, or a literal
+    fn factor(&mut self, after: Span) -> Result<Value, Error> {
+        let depth = self.depth;
+        let value = self.signed(after);
+        self.depth = depth;
+        value
+    }
+
+    /// Signs are collected in a loop rather than recursed into, and applied innermost first
+    fn signed(&mut self, mut after: Span) -> Result<Value, Error> {
+        let mut signs = Vec::new();
+        let mut value = loop {
+            let token = self.peek().ok_or(Error::InvalidData { span: after })?;
+            let op = match Operator::from_token(token) {
+                Some(op) => op,
+                None => break self.literal()?,
+            };
+            self.bump();
+            self.depth += 1;
+            if self.depth > MAX_DEPTH {
+                return Err(Error::InvalidData { span: token.span });
+            }
+
+            match op {
+                Operator::Open => break self.group(token)?,
+                Operator::Add | Operator::Sub => {
+                    signs.push((op, token.span));
+                    after = token.span;
+                }
+                _ => return Err(Error::InvalidData { span: token.span }),
+            }
+        };
+
+        for (op, sign) in signs.into_iter().rev() {
+            let span = Span::new(sign.start, value.span.end);
+            let kind = match (op, value.kind) {
+                (Operator::Add, kind) => kind,
+                (_, Kind::Duration(nanos)) => {
+                    Kind::Duration(nanos.checked_neg().ok_or(Error::Overflow { span })?)
+                }
+                (_, Kind::Number(ratio)) => {
+                    Kind::Number(ratio.neg().ok_or(Error::Overflow { span })?)
+                }
+            };
+            value = Value { kind, span };
+        }
+        Ok(value)
+    }
+
+    /// The rest of `'(' sum ')'`, after the `open` parenthesis
+    fn group(&mut self, open: Token<'a>) -> Result<Value, Error> {
+        let inner = self.sum(open.span)?;
+        match self.bump() {
+            Some(close) if Operator::from_token(close) == Some(Operator::Close) => Ok(Value {
+                kind: inner.kind,
+                span: Span::new(open.span.start, close.span.end),
+            }),
+            _ => Err(Error::InvalidData { span: open.span }),
+        }
+    }
+
+    /// A number, or a duration made of everything up to the next operator
+    fn literal(&mut self) -> Result<Value, Error> {
+        let (mut span, mut number, mut len) = (None::<Span>, None, 0);
+        loop {
+            let mut tokens = self.tokens;
+            let token = match tokens.next() {
+                Some(token) if Operator::from_token(token).is_none() => token,
+                _ => break,
+            };
+            self.tokens = tokens;
+            if token.kind == TokenKind::Whitespace {
+                continue;
+            }
+
+            len += 1;
+            span = Some(match span {
+                Some(span) => Span::new(span.start, token.span.end),
+                None => token.span,
+            });
+            if let TokenKind::Number { whole, fraction } = token.kind {
+                number.replace((whole, fraction.unwrap_or_default()));
+            }
+        }
+        let span = span.expect("literals start with something other than an operator");
+
+        let kind = match number {
+            Some((whole, fraction)) if len == 1 => {
+                let overflow = Error::Overflow { span };
+                let mut num: i128 = 0;
+                for digit in whole.bytes().chain(fraction.bytes()) {
+                    num = num
+                        .checked_mul(10)
+                        .and_then(|num| num.checked_add((digit - b'0') as i128))
+                        .ok_or(overflow)?;
+                }
+                let den = 10_i128.checked_pow(fraction.len() as u32).ok_or(overflow)?;
+                Kind::Number(Ratio::new(num, den).ok_or(overflow)?)
+            }
+            _ => {
+                let input = &self.input[span.start..span.end];
+                let options = ParserOptions::new().strict(true);
+                Kind::Duration(parse(input, &options).map_err(|err| err.shift(span.start))?)
+            }
+        };
+        Ok(Value { kind, span })
+    }
+}
+
+/// Apply a binary operator, durations can only be scaled by numbers
+fn apply(op: Operator, left: Value, right: Value) -> Result<Value, Error> {
+    let span = Span::new(left.span.start, right.span.end);
+    let zero = match right.kind {
+        Kind::Duration(nanos) => nanos == 0,
+        Kind::Number(ratio) => ratio.num == 0,
+    };
+    if op == Operator::Div && zero {
+        return Err(Error::InvalidData { span: right.span });
+    }
+
+    let overflow = Error::Overflow { span };
+    let kind = match (op, left.kind, right.kind) {
+        (Operator::Add, Kind::Duration(a), Kind::Duration(b)) => {
+            a.checked_add(b).map(Kind::Duration)
+        }
+        (Operator::Sub, Kind::Duration(a), Kind::Duration(b)) => {
+            a.checked_sub(b).map(Kind::Duration)
+        }
+        (Operator::Add, Kind::Number(a), Kind::Number(b)) => a.add(b).map(Kind::Number),
+        (Operator::Sub, Kind::Number(a), Kind::Number(b)) => {
+            b.neg().and_then(|b| a.add(b)).map(Kind::Number)
+        }
+        (Operator::Mul, Kind::Duration(nanos), Kind::Number(ratio))
+        | (Operator::Mul, Kind::Number(ratio), Kind::Duration(nanos)) => {
+            return scale(nanos, ratio, span)
+        }
+        (Operator::Mul, Kind::Number(a), Kind::Number(b)) => a.mul(b).map(Kind::Number),
+        (Operator::Div, Kind::Duration(nanos), Kind::Number(ratio)) => {
+            return scale(nanos, ratio.recip().ok_or(overflow)?, span)
+        }
+        (Operator::Div, Kind::Number(a), Kind::Number(b)) => {
+            b.recip().and_then(|b| a.mul(b)).map(Kind::Number)
+        }
+        (Operator::Div, Kind::Duration(a), Kind::Duration(b)) => Ratio::new(a, b).map(Kind::Number),
+        _ => return Err(Error::InvalidData { span }),
+    };
+    Ok(Value {
+        kind: kind.ok_or(overflow)?,
+        span,
+    })
+}
+
+/// Multiply the duration by the ratio, which has to give whole nanoseconds
+fn scale(nanos: i128, ratio: Ratio, span: Span) -> Result<Value, Error> {
+    let nanos = nanos
+        .checked_mul(ratio.num)
+        .ok_or(Error::Overflow { span })?;
+    if nanos % ratio.den != 0 {
+        return Err(Error::Precision { span });
+    }
+    Ok(Value {
+        kind: Kind::Duration(nanos / ratio.den),
+        span,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expression() {
+        const SEC: i128 = 1_000_000_000;
+        const MIN: i128 = 60 * SEC;
+
+        let tests = &[
+            ("1h -5m", 55 * MIN),
+            ("1h-5m", 55 * MIN),
+            ("-1h 30m", -90 * MIN),
+            ("1h - 2h", -60 * MIN),
+            ("1:30:00 / 2", 45 * MIN),
+            ("(1 + 2) x 20m", 60 * MIN),
+            ("1 / 3 * 3h", 60 * MIN),
+            ("10s / 4", 5 * SEC / 2),
+            ("1.25 X 4m", 5 * MIN),
+            ("2 × 1m", 2 * MIN),
+            ("((1h))", 60 * MIN),
+            ("3 hours 5 minutes * 2", 370 * MIN),
+            (" 1h / 4 ", 15 * MIN),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_expression_nanos(input).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let tests = &[
+            (
+                "",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "1h +",
+                Error::InvalidData {
+                    span: Span::new(3, 4),
+                },
+            ),
+            (
+                "1h * * 2",
+                Error::InvalidData {
+                    span: Span::new(5, 6),
+                },
+            ),
+            (
+                "(1h",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1h)",
+                Error::UnexpectedCharacter {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "2 * 3",
+                Error::InvalidData {
+                    span: Span::new(0, 5),
+                },
+            ),
+            (
+                "2 - 1h",
+                Error::InvalidData {
+                    span: Span::new(0, 6),
+                },
+            ),
+            (
+                "2 / 1h",
+                Error::InvalidData {
+                    span: Span::new(0, 6),
+                },
+            ),
+            (
+                "1h / 0",
+                Error::InvalidData {
+                    span: Span::new(5, 6),
+                },
+            ),
+            (
+                "1h / (1m - 1m)",
+                Error::InvalidData {
+                    span: Span::new(5, 14),
+                },
+            ),
+            (
+                "1s / 3",
+                Error::Precision {
+                    span: Span::new(0, 6),
+                },
+            ),
+            (
+                "1h, 5m",
+                Error::UnexpectedCharacter {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "1h + 1m 1h",
+                Error::OutOfOrder {
+                    span: Span::new(8, 10),
+                    previous: Span::new(5, 7),
+                },
+            ),
+            (
+                "170141183460469231731687303715884105727ns + 1ns",
+                Error::Overflow {
+                    span: Span::new(0, 47),
+                },
+            ),
+            (
+                "1000000000000y * 1000000000000000",
+                Error::Overflow {
+                    span: Span::new(0, 33),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_expression_nanos(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let nested = |open: &str, close: &str, depth: usize| {
+            format!("{}1h{}", open.repeat(depth), close.repeat(depth))
+        };
+        assert_eq!(parse_expression_nanos(&nested("(", ")", 64)), Ok(60 * MIN));
+        assert_eq!(parse_expression_nanos(&nested("-", "", 64)), Ok(60 * MIN));
+        assert_eq!(parse_expression_nanos(&nested("-(", ")", 32)), Ok(60 * MIN));
+
+        let tests = &[
+            (nested("(", ")", 100_000), 64),
+            (nested("-", "", 100_000), 64),
+            (nested("+(", ")", 50_000), 64),
+        ];
+
+        for (input, at) in tests {
+            assert_eq!(
+                parse_expression_nanos(input).unwrap_err(),
+                Error::InvalidData {
+                    span: Span::new(*at, *at + 1),
+                },
+                "input: {}...",
+                &input[..10]
+            );
+        }
+    }
+}
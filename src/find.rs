@@ -0,0 +1,185 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::{parse, DurationParser, Error, Magnitude, ParserOptions, Span};
+use std::time::Duration;
+
+/// A duration found in some text, see [`find_durations`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DurationMatch {
+    span: Span,
+    nanos: u128,
+}
+
+impl DurationMatch {
+    /// Where the duration is in the text
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn nanos(&self) -> u128 {
+        self.nanos
+    }
+
+    /// The duration, or [`Error::Overflow`] at its span if it doesn't fit
+    pub fn duration(&self) -> Result<Duration, Error> {
+        <Duration as DurationParser>::from_nanos(self.nanos as i128, self.span)
+    }
+}
+
+/// An iterator over the durations in some text, see [`find_durations`]
+#[derive(Clone)]
+pub struct FindDurations<'a> {
+    text: &'a str,
+    tokens: Lexer<'a>,
+}
+
+/// Find every duration in some text, e.g. the `3h 20m` in `remind me in 3h 20m to stretch`
+///
+/// A duration is a run of components, like `3h` or `20 minutes`, separated by
+/// whitespace or nothing at all. Components have to be in order with each unit
+/// at most once, so `1m 1h` is two durations. Words that merely start with a
+/// unit, like the `s` in `3 stretches`, and numbers attached to other words,
+/// like `v2s`, aren't durations. Signs and clock-style components aren't
+/// recognized.
+///
+/// ```rust
+/// use simple_duration_parse::find_durations;
+/// use std::time::Duration;
+///
+/// let text = "remind me in 3h 20m to stretch";
+/// let found = find_durations(text).collect::<Vec<_>>();
+/// assert_eq!(found.len(), 1);
+/// let span = found[0].span();
+/// assert_eq!(&text[span.start..span.end], "3h 20m");
+/// assert_eq!(found[0].duration().unwrap(), Duration::from_secs(12000));
+///
+/// let rest = format!("{}{}", &text[..span.start], &text[span.end..]);
+/// assert_eq!(rest, "remind me in  to stretch");
+/// ```
+pub fn find_durations(text: &str) -> FindDurations<'_> {
+    FindDurations {
+        text,
+        tokens: Lexer::new(text),
+    }
+}
+
+impl<'a> FindDurations<'a> {
+    /// Lex a single component, `<number>[ ]<unit>`, ending the `tokens` after it
+    fn component(&self, tokens: &mut Lexer<'a>) -> Option<Span> {
+        let number = tokens.next_if(|token| matches!(token.kind, TokenKind::Number { .. }))?;
+        tokens.next_if(|token| token.kind == TokenKind::Whitespace);
+        match tokens.next()? {
+            Token {
+                kind: TokenKind::Word(word),
+                span,
+            } if Magnitude::from_name(word).is_some() => {
+                Some(Span::new(number.span.start, span.end))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the text at `span` is attached to the word or number before it
+    fn attached(&self, span: Span) -> bool {
+        self.text[..span.start]
+            .chars()
+            .next_back()
+            .is_some_and(|c| c.is_alphanumeric() || c == '.')
+    }
+
+    /// Parse a run of components the same way as [`parse_nanos`](crate::parse_nanos)
+    fn parse(&self, span: Span) -> Option<u128> {
+        match parse(&self.text[span.start..span.end], &ParserOptions::new()) {
+            Ok(nanos) => Some(nanos as u128),
+            Err(..) => None,
+        }
+    }
+}
+
+impl<'a> Iterator for FindDurations<'a> {
+    type Item = DurationMatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut tokens = self.tokens;
+            let first = match self.component(&mut tokens) {
+                Some(first) if !self.attached(first) => first,
+                _ => {
+                    self.tokens.next()?;
+                    continue;
+                }
+            };
+            self.tokens = tokens;
+
+            let mut found = match self.parse(first) {
+                Some(nanos) => DurationMatch { span: first, nanos },
+                None => continue,
+            };
+
+            // extend the match for as long as the components still parse together
+            loop {
+                let mut tokens = self.tokens;
+                tokens.next_if(|token| token.kind == TokenKind::Whitespace);
+                let span = match self.component(&mut tokens) {
+                    Some(component) => Span::new(first.start, component.end),
+                    None => break,
+                };
+                match self.parse(span) {
+                    Some(nanos) => found = DurationMatch { span, nanos },
+                    None => break,
+                }
+                self.tokens = tokens;
+            }
+            return Some(found);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find() {
+        const MIN: u128 = 60 * 1_000_000_000;
+
+        let tests: &[(&str, &[(&str, u128)])] = &[
+            ("remind me in 3h 20m to stretch", &[("3h 20m", 200 * MIN)]),
+            ("3h20m", &[("3h20m", 200 * MIN)]),
+            (
+                "wait 5 minutes, then 1 hour",
+                &[("5 minutes", 5 * MIN), ("1 hour", 60 * MIN)],
+            ),
+            ("1m 1h", &[("1m", MIN), ("1h", 60 * MIN)]),
+            ("1m 1m", &[("1m", MIN), ("1m", MIN)]),
+            (
+                "took 1.5h and 90s",
+                &[("1.5h", 90 * MIN), ("90s", 3 * MIN / 2)],
+            ),
+            ("2 days ago", &[("2 days", 2 * 24 * 60 * MIN)]),
+            ("-5m", &[("5m", 5 * MIN)]),
+            ("3µs", &[("3µs", 3_000)]),
+            ("3 stretches", &[]),
+            ("v2s 3d", &[("3d", 3 * 24 * 60 * MIN)]),
+            ("1.2.3s", &[]),
+            ("some stray s m h d", &[]),
+            ("at 10:30", &[]),
+            ("0s 05m", &[]),
+            ("", &[]),
+        ];
+
+        for (input, expected) in tests {
+            let found = find_durations(input)
+                .map(|found| (&input[found.span().start..found.span().end], found.nanos()))
+                .collect::<Vec<_>>();
+            assert_eq!(found, *expected, "input: {}", input);
+        }
+
+        let found = find_durations("in 1000000000000y").next().unwrap();
+        assert_eq!(
+            found.duration().unwrap_err(),
+            Error::Overflow {
+                span: Span::new(3, 17),
+            }
+        );
+    }
+}
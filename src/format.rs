@@ -0,0 +1,290 @@
+use crate::{Error, Magnitude, ParserOptions};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Options for turning a duration back into its human-readable form
+///
+/// The default emits every non-zero component, largest first:
+///
+/// ```rust
+/// use simple_duration_parse::FormatOptions;
+/// use std::time::Duration;
+///
+/// let duration = Duration::new(3600 + 3, 500_000_000);
+/// assert_eq!(FormatOptions::new().format(duration), "1h 3s 500ms");
+/// assert_eq!(FormatOptions::new().max_components(2).format(duration), "1h 3s");
+/// assert_eq!(FormatOptions::new().omit_zero(false).format(duration), "1h 0m 3s 500ms");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FormatOptions {
+    max_components: Option<usize>,
+    omit_zero: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatOptions {
+    pub const fn new() -> Self {
+        Self {
+            max_components: None,
+            omit_zero: true,
+        }
+    }
+
+    /// Stop after this many components, truncating the rest
+    ///
+    /// At least one component is always emitted
+    pub const fn max_components(mut self, max: usize) -> Self {
+        self.max_components = Some(max);
+        self
+    }
+
+    /// Whether to skip zero components between the largest and smallest ones
+    ///
+    /// When disabled, components are emitted down to seconds (or the smallest
+    /// non-zero sub-second component). Note that the parser rejects zero components.
+    pub const fn omit_zero(mut self, omit: bool) -> Self {
+        self.omit_zero = omit;
+        self
+    }
+
+    /// Format the duration into a string
+    pub fn format(&self, duration: Duration) -> String {
+        let mut out = String::new();
+        self.write(duration.as_nanos(), &mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    pub(crate) fn write(&self, nanos: u128, w: &mut impl fmt::Write) -> fmt::Result {
+        if nanos == 0 {
+            return w.write_str("0s");
+        }
+
+        // zeros are only shown down to seconds, or the last sub-second component
+        let smallest = Magnitude::DESCENDING
+            .iter()
+            .copied()
+            .filter(|&m| m <= Magnitude::Second)
+            .find(|m| nanos % m.to_nanos() == 0)
+            .unwrap_or(Magnitude::Nanosecond);
+        let max = self.max_components.unwrap_or(usize::MAX).max(1);
+
+        let (mut rest, mut count) = (nanos, 0);
+        for &magnitude in &Magnitude::DESCENDING {
+            if count == max || magnitude < smallest {
+                break;
+            }
+
+            let n = rest / magnitude.to_nanos();
+            rest %= magnitude.to_nanos();
+            if n == 0 && (count == 0 || self.omit_zero) {
+                continue;
+            }
+
+            if count > 0 {
+                w.write_char(' ')?;
+            }
+            write!(w, "{}{}", n, magnitude.suffix())?;
+            count += 1;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`Duration`] that displays in its canonical human-readable form, and parses from it
+///
+/// Parsing is [strict](ParserOptions::strict) and allows zero, so every formatted value
+/// parses back into the same duration. Formatting honors width and alignment, e.g. `{:>10}`.
+/// With the `serde` feature, it (de)serializes like the [`serde`](crate::serde) module.
+///
+/// ```rust
+/// use simple_duration_parse::HumanDuration;
+/// use std::time::Duration;
+///
+/// let duration = HumanDuration(Duration::from_secs(60 * 60 * 24 * 8 + 3661));
+/// assert_eq!(duration.to_string(), "1w 1d 1h 1m 1s");
+///
+/// let duration: HumanDuration = "1h 30m".parse().unwrap();
+/// assert_eq!(duration.as_secs(), 5400);
+/// assert_eq!(Duration::from(duration), Duration::from_secs(5400));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // formatted whole first so that width and alignment apply to all of it
+        f.pad(&FormatOptions::new().format(self.0))
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        ParserOptions::new()
+            .strict(true)
+            .allow_zero(true)
+            .parse(input)
+            .map(Self)
+    }
+}
+
+impl Deref for HumanDuration {
+    type Target = Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Duration> for HumanDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(duration: HumanDuration) -> Self {
+        duration.0
+    }
+}
+
+/// Format seconds into the canonical form accepted by [`parse_secs`](crate::parse_secs)
+///
+/// For every non-zero input `parse_secs(&format_secs(secs)) == Ok(secs)`.
+/// Zero is formatted as `0s`, which the parser rejects.
+///
+/// ```rust
+/// use simple_duration_parse::{format_secs, parse_secs};
+///
+/// assert_eq!(format_secs(3723), "1h 2m 3s");
+/// assert_eq!(parse_secs(&format_secs(3723)).unwrap(), 3723);
+/// ```
+pub fn format_secs(secs: u64) -> String {
+    FormatOptions::new().format(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_nanos, parse_secs};
+
+    #[test]
+    fn format() {
+        let tests = &[
+            (Duration::from_secs(0), "0s"),
+            (Duration::from_secs(1), "1s"),
+            (Duration::from_secs(60), "1m"),
+            (Duration::from_secs(3601), "1h 1s"),
+            (Duration::from_secs(60 * 60 * 24 * 366), "1y 1d"),
+            (Duration::from_millis(1500), "1s 500ms"),
+            (Duration::from_nanos(1_001), "1us 1ns"),
+            (
+                Duration::new(u64::MAX, 999_999_999),
+                "584942417355y 3w 5d 7h 15s 999ms 999us 999ns",
+            ),
+        ];
+
+        for (duration, expected) in tests {
+            assert_eq!(HumanDuration(*duration).to_string(), *expected);
+        }
+
+        let duration = HumanDuration(Duration::from_secs(90));
+        assert_eq!(format!("[{:>10}]", duration), "[    1m 30s]");
+        assert_eq!(format!("[{:-<8}]", duration), "[1m 30s--]");
+
+        let duration = Duration::new(60 * 60 * 24 + 5, 7_000);
+        let tests = &[
+            (FormatOptions::new().max_components(0), "1d"),
+            (FormatOptions::new().max_components(2), "1d 5s"),
+            (FormatOptions::new().omit_zero(false), "1d 0h 0m 5s 0ms 7us"),
+            (
+                FormatOptions::new().omit_zero(false).max_components(3),
+                "1d 0h 0m",
+            ),
+        ];
+
+        for (options, expected) in tests {
+            assert_eq!(options.format(duration), *expected);
+        }
+
+        assert_eq!(
+            FormatOptions::new()
+                .omit_zero(false)
+                .format(Duration::from_secs(60 * 60)),
+            "1h 0m 0s"
+        );
+    }
+
+    #[test]
+    fn human_duration() {
+        let tests = &[
+            ("0s", Duration::from_secs(0)),
+            ("1h 30m", Duration::from_secs(5400)),
+            ("1.5s", Duration::from_millis(1500)),
+        ];
+
+        for (input, expected) in tests {
+            let duration = input.parse::<HumanDuration>().unwrap();
+            assert_eq!(*duration, *expected, "input: {}", input);
+        }
+
+        assert_eq!(
+            "1m 1h".parse::<HumanDuration>().unwrap_err(),
+            Error::OutOfOrder {
+                span: crate::Span::new(3, 5),
+                previous: crate::Span::new(0, 2),
+            }
+        );
+        assert_eq!(
+            "-1m".parse::<HumanDuration>().unwrap_err(),
+            Error::Negative {
+                span: crate::Span::new(0, 3),
+            }
+        );
+
+        for input in &["", "foobar", "1h foo"] {
+            assert!(input.parse::<HumanDuration>().is_err(), "input: {}", input);
+        }
+
+        let mut durations = [
+            HumanDuration::from(Duration::from_secs(60)),
+            HumanDuration::from(Duration::from_secs(1)),
+        ];
+        durations.sort();
+        assert_eq!(durations[0], HumanDuration(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut secs = 1_u64;
+        while let Some(next) = secs.checked_mul(7) {
+            for secs in &[secs, secs + 1, secs - 1, next - 1] {
+                if *secs > 0 {
+                    assert_eq!(parse_secs(&format_secs(*secs)).unwrap(), *secs);
+                }
+            }
+            secs = next;
+        }
+        assert_eq!(parse_secs(&format_secs(u64::MAX)).unwrap(), u64::MAX);
+
+        let mut nanos = 1_u128;
+        while nanos < Duration::MAX.as_nanos() {
+            let duration =
+                Duration::new((nanos / 1_000_000_000) as _, (nanos % 1_000_000_000) as _);
+            let formatted = HumanDuration(duration).to_string();
+            assert_eq!(parse_nanos(&formatted).unwrap(), nanos, "{}", formatted);
+            assert_eq!(formatted.parse::<HumanDuration>().unwrap().0, duration);
+            nanos = nanos * 13 + 1;
+        }
+    }
+}
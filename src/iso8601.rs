@@ -0,0 +1,351 @@
+use crate::{scale, Error, Magnitude, Span};
+use std::fmt::Write as _;
+use std::time::Duration;
+
+/// Parse an ISO 8601 duration (`PnYnMnWnDTnHnMnS`) into nanoseconds
+///
+/// Every designator is optional, but they must appear in order and at least one
+/// is required. The last component may have a fraction, separated by `.` or `,`.
+///
+/// Years and months don't have a fixed length, so any non-zero amount of them
+/// returns [`Error::CalendarUnit`]
+///
+/// ```rust
+/// use simple_duration_parse::{parse_iso8601_nanos, Error, Span};
+///
+/// let tests = &[
+///     ("PT1H30M", 90 * 60 * 1_000_000_000),
+///     ("P3DT4H", (3 * 24 + 4) * 60 * 60 * 1_000_000_000),
+///     ("P2W", 14 * 24 * 60 * 60 * 1_000_000_000),
+///     ("PT1.5S", 1_500_000_000),
+///     ("PT0S", 0),
+/// ];
+///
+/// for (input, expected) in tests {
+///     assert_eq!(parse_iso8601_nanos(input).unwrap(), *expected);
+/// }
+///
+/// assert_eq!(
+///     parse_iso8601_nanos("P1M").unwrap_err(),
+///     Error::CalendarUnit { span: Span::new(1, 3) }
+/// );
+/// ```
+pub fn parse_iso8601_nanos(input: &str) -> Result<u128, Error> {
+    let bytes = input.as_bytes();
+    let unexpected = |pos: usize| {
+        let len = input[pos..].chars().next().map_or(0, char::len_utf8);
+        Error::UnexpectedCharacter {
+            span: Span::new(pos, pos + len),
+        }
+    };
+    let digits = |pos: usize| {
+        pos + bytes[pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count()
+    };
+
+    match bytes.first() {
+        Some(b'P') => {}
+        Some(..) => return Err(unexpected(0)),
+        None => {
+            return Err(Error::InvalidData {
+                span: Span::new(0, 0),
+            })
+        }
+    }
+
+    // designators are ranked by where they can appear, each at most once
+    let mut previous: Option<(usize, Span)> = None;
+    let mut fraction: Option<Span> = None;
+    let mut time = false;
+    let mut acc: u128 = 0;
+    let mut pos = 1;
+
+    while pos < bytes.len() {
+        if bytes[pos] == b'T' && !time {
+            time = true;
+            pos += 1;
+            if !bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+                return Err(Error::InvalidData {
+                    span: Span::new(pos - 1, pos),
+                });
+            }
+            continue;
+        }
+
+        if !bytes[pos].is_ascii_digit() {
+            return Err(unexpected(pos));
+        }
+
+        let start = pos;
+        pos = digits(pos);
+        let whole = &input[start..pos];
+        let frac = match bytes.get(pos) {
+            Some(b'.') | Some(b',') => {
+                let end = digits(pos + 1);
+                if end == pos + 1 {
+                    return Err(Error::InvalidData {
+                        span: Span::new(start, end),
+                    });
+                }
+                let frac = &input[pos + 1..end];
+                pos = end;
+                frac
+            }
+            _ => "",
+        };
+
+        let (rank, magnitude) = match (time, bytes.get(pos)) {
+            (false, Some(b'Y')) => (0, None),
+            (false, Some(b'M')) => (1, None),
+            (false, Some(b'W')) => (2, Some(Magnitude::Week)),
+            (false, Some(b'D')) => (3, Some(Magnitude::Day)),
+            (true, Some(b'H')) => (4, Some(Magnitude::Hour)),
+            (true, Some(b'M')) => (5, Some(Magnitude::Minute)),
+            (true, Some(b'S')) => (6, Some(Magnitude::Second)),
+            (_, Some(..)) => return Err(unexpected(pos)),
+            (_, None) => {
+                return Err(Error::InvalidData {
+                    span: Span::new(start, pos),
+                })
+            }
+        };
+        pos += 1;
+        let span = Span::new(start, pos);
+
+        if let Some(span) = fraction {
+            // only the smallest component can have a fraction
+            return Err(Error::InvalidData { span });
+        }
+        if !frac.is_empty() {
+            fraction.replace(span);
+        }
+
+        match previous {
+            Some((r, previous)) if r == rank => return Err(Error::AlreadySeen { span, previous }),
+            Some((r, previous)) if r > rank => return Err(Error::OutOfOrder { span, previous }),
+            _ => previous = Some((rank, span)),
+        }
+
+        let nanos = match magnitude {
+            Some(magnitude) => scale(whole, frac, magnitude, span)?,
+            None if whole.bytes().chain(frac.bytes()).all(|c| c == b'0') => 0,
+            None => return Err(Error::CalendarUnit { span }),
+        };
+        acc = acc.checked_add(nanos).ok_or(Error::Overflow { span })?;
+    }
+
+    if previous.is_none() {
+        return Err(Error::InvalidData {
+            span: Span::new(0, input.len()),
+        });
+    }
+
+    Ok(acc)
+}
+
+/// Format the duration as an ISO 8601 duration, using days as the largest unit
+///
+/// ```rust
+/// use simple_duration_parse::format_iso8601;
+/// use std::time::Duration;
+///
+/// assert_eq!(format_iso8601(Duration::from_secs(5400)), "PT1H30M");
+/// assert_eq!(format_iso8601(Duration::from_millis(100_001_500)), "P1DT3H46M41.5S");
+/// assert_eq!(format_iso8601(Duration::from_secs(0)), "PT0S");
+/// ```
+pub fn format_iso8601(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let nanos = duration.subsec_nanos();
+    let (days, hours, minutes, secs) = (
+        secs / 86_400,
+        secs % 86_400 / 3_600,
+        secs % 3_600 / 60,
+        secs % 60,
+    );
+
+    let mut out = String::from("P");
+    if days > 0 {
+        write!(out, "{}D", days).unwrap();
+    }
+    if hours == 0 && minutes == 0 && secs == 0 && nanos == 0 {
+        if days == 0 {
+            out.push_str("T0S");
+        }
+        return out;
+    }
+
+    out.push('T');
+    if hours > 0 {
+        write!(out, "{}H", hours).unwrap();
+    }
+    if minutes > 0 {
+        write!(out, "{}M", minutes).unwrap();
+    }
+    match nanos {
+        0 if secs > 0 => write!(out, "{}S", secs).unwrap(),
+        0 => {}
+        nanos => {
+            let frac = format!("{:09}", nanos);
+            write!(out, "{}.{}S", secs, frac.trim_end_matches('0')).unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NANOS_PER_SEC;
+
+    #[test]
+    fn parse() {
+        const SEC: u128 = NANOS_PER_SEC;
+        let tests = &[
+            ("PT1H30M", 90 * 60 * SEC),
+            ("P3DT4H", (3 * 24 + 4) * 60 * 60 * SEC),
+            ("P2W", 14 * 24 * 60 * 60 * SEC),
+            ("P1W2D", 9 * 24 * 60 * 60 * SEC),
+            ("PT1.5S", 3 * SEC / 2),
+            ("PT1,5S", 3 * SEC / 2),
+            ("PT0.000000001S", 1),
+            ("PT0.5H", 30 * 60 * SEC),
+            ("PT05M", 5 * 60 * SEC),
+            ("PT0S", 0),
+            ("P0D", 0),
+            ("P0Y0M1D", 24 * 60 * 60 * SEC),
+            ("PT36H", 36 * 60 * 60 * SEC),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_iso8601_nanos(input).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let tests = &[
+            (
+                "",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "P",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "PT",
+                Error::InvalidData {
+                    span: Span::new(1, 2),
+                },
+            ),
+            (
+                "P1DT",
+                Error::InvalidData {
+                    span: Span::new(3, 4),
+                },
+            ),
+            (
+                "PT1",
+                Error::InvalidData {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "PT1.S",
+                Error::InvalidData {
+                    span: Span::new(2, 4),
+                },
+            ),
+            (
+                "1H",
+                Error::UnexpectedCharacter {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "PT1H ",
+                Error::UnexpectedCharacter {
+                    span: Span::new(4, 5),
+                },
+            ),
+            (
+                "P1H",
+                Error::UnexpectedCharacter {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "PT1D",
+                Error::UnexpectedCharacter {
+                    span: Span::new(3, 4),
+                },
+            ),
+            (
+                "P1Y",
+                Error::CalendarUnit {
+                    span: Span::new(1, 3),
+                },
+            ),
+            (
+                "P1DT1M1M",
+                Error::AlreadySeen {
+                    span: Span::new(6, 8),
+                    previous: Span::new(4, 6),
+                },
+            ),
+            (
+                "P1D2W",
+                Error::OutOfOrder {
+                    span: Span::new(3, 5),
+                    previous: Span::new(1, 3),
+                },
+            ),
+            (
+                "PT1.5H30M",
+                Error::InvalidData {
+                    span: Span::new(2, 6),
+                },
+            ),
+            (
+                "PT0.0000000001S",
+                Error::Precision {
+                    span: Span::new(2, 15),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_iso8601_nanos(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut nanos = 1_u128;
+        while nanos < Duration::MAX.as_nanos() {
+            let duration =
+                Duration::new((nanos / NANOS_PER_SEC) as _, (nanos % NANOS_PER_SEC) as _);
+            let formatted = format_iso8601(duration);
+            assert_eq!(
+                parse_iso8601_nanos(&formatted).unwrap(),
+                nanos,
+                "{}",
+                formatted
+            );
+            nanos = nanos * 13 + 1;
+        }
+    }
+}
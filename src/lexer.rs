@@ -0,0 +1,283 @@
+use crate::Span;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum TokenKind<'a> {
+    /// Digits with an optional fraction, e.g. `1` `1.5` or `1.`
+    Number {
+        whole: &'a str,
+        fraction: Option<&'a str>,
+    },
+    /// A run of ASCII letters and other non-ASCII characters
+    Word(&'a str),
+    /// A run of whitespace
+    Whitespace,
+    Other(char),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub span: Span,
+}
+
+#[derive(Copy, Clone)]
+pub(crate) struct Lexer<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+/// The characters a token can be made of
+#[derive(Copy, Clone)]
+enum Class {
+    Digit,
+    Alphabetic,
+    Whitespace,
+}
+
+impl Class {
+    const fn contains_ascii(self, byte: u8) -> bool {
+        match self {
+            Self::Digit => byte.is_ascii_digit(),
+            Self::Alphabetic => byte.is_ascii_alphabetic(),
+            Self::Whitespace => matches!(byte, b'\t'..=b'\r' | b' '),
+        }
+    }
+
+    const fn contains(self, c: char) -> bool {
+        match self {
+            Self::Digit => c.is_ascii_digit(),
+            // anything beyond ASCII is part of a word, short of the symbols used as operators
+            Self::Alphabetic => {
+                c.is_ascii_alphabetic() || !(c.is_ascii() || is_whitespace(c) || is_operator(c))
+            }
+            Self::Whitespace => is_whitespace(c),
+        }
+    }
+}
+
+impl<'a> Lexer<'a> {
+    pub const fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    /// The next byte, which is the start of the next token
+    pub const fn peek_byte(&self) -> Option<u8> {
+        let bytes = self.input.as_bytes();
+        match self.pos < bytes.len() {
+            true => Some(bytes[self.pos]),
+            false => None,
+        }
+    }
+
+    /// Advance over the next token only if it matches `f`
+    pub fn next_if(&mut self, f: impl Fn(&Token<'a>) -> bool) -> Option<Token<'a>> {
+        let mut lexer = *self;
+        let token = lexer.next().filter(f)?;
+        *self = lexer;
+        Some(token)
+    }
+
+    /// Advance over the next token if it's whitespace
+    pub const fn skip_whitespace(&mut self) {
+        let mut lexer = *self;
+        if let Some(Token {
+            kind: TokenKind::Whitespace,
+            ..
+        }) = lexer.next_token()
+        {
+            *self = lexer;
+        }
+    }
+
+    /// Advance over the characters in `class`
+    // inlined so that every call site gets a loop specialized to its class
+    #[inline(always)]
+    const fn take_while(&mut self, class: Class) -> &'a str {
+        let start = self.pos;
+        let bytes = self.input.as_bytes();
+        while self.pos < bytes.len() {
+            // only decode characters when there's something other than ASCII
+            let len = match bytes[self.pos] {
+                byte if byte.is_ascii() && class.contains_ascii(byte) => 1,
+                byte if byte.is_ascii() => break,
+                _ => match decode(bytes, self.pos) {
+                    (c, len) if class.contains(c) => len,
+                    _ => break,
+                },
+            };
+            self.pos += len;
+        }
+        slice(self.input, start, self.pos)
+    }
+
+    /// The next token, this is [`Iterator::next`] for `const` contexts
+    pub const fn next_token(&mut self) -> Option<Token<'a>> {
+        let start = self.pos;
+        let byte = match self.peek_byte() {
+            Some(byte) => byte,
+            None => return None,
+        };
+
+        let kind = match byte {
+            b'0'..=b'9' => {
+                let whole = self.take_while(Class::Digit);
+                let fraction = match self.peek_byte() {
+                    Some(b'.') => {
+                        self.pos += 1;
+                        Some(self.take_while(Class::Digit))
+                    }
+                    _ => None,
+                };
+                TokenKind::Number { whole, fraction }
+            }
+            b'a'..=b'z' | b'A'..=b'Z' => TokenKind::Word(self.take_while(Class::Alphabetic)),
+            b'\t'..=b'\r' | b' ' => {
+                self.take_while(Class::Whitespace);
+                TokenKind::Whitespace
+            }
+            _ if byte.is_ascii() => {
+                self.pos += 1;
+                TokenKind::Other(byte as char)
+            }
+            _ => {
+                let (ch, len) = decode(self.input.as_bytes(), start);
+                if Class::Alphabetic.contains(ch) {
+                    TokenKind::Word(self.take_while(Class::Alphabetic))
+                } else if Class::Whitespace.contains(ch) {
+                    self.take_while(Class::Whitespace);
+                    TokenKind::Whitespace
+                } else {
+                    self.pos += len;
+                    TokenKind::Other(ch)
+                }
+            }
+        };
+
+        Some(Token {
+            kind,
+            span: Span::new(start, self.pos),
+        })
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_token()
+    }
+}
+
+/// `char::is_whitespace`, which is only `const` from Rust 1.87
+const fn is_whitespace(c: char) -> bool {
+    matches!(
+        c,
+        '\t'..='\r'
+            | ' '
+            | '\u{85}'
+            | '\u{a0}'
+            | '\u{1680}'
+            | '\u{2000}'..='\u{200a}'
+            | '\u{2028}'
+            | '\u{2029}'
+            | '\u{202f}'
+            | '\u{205f}'
+            | '\u{3000}'
+    )
+}
+
+/// The non-ASCII symbols with a meaning of their own, `±` for tolerances and `×` in expressions
+const fn is_operator(c: char) -> bool {
+    matches!(c, '±' | '×')
+}
+
+/// Decode the character starting at `pos`, along with its length in bytes
+pub(crate) const fn decode(bytes: &[u8], pos: usize) -> (char, usize) {
+    let (len, mut c) = match bytes[pos] {
+        // nearly everything is ASCII
+        byte @ 0x00..=0x7F => return (byte as char, 1),
+        byte @ 0xC0..=0xDF => (2, (byte & 0x1F) as u32),
+        byte @ 0xE0..=0xEF => (3, (byte & 0x0F) as u32),
+        byte => (4, (byte & 0x07) as u32),
+    };
+    let mut i = 1;
+    while i < len {
+        c = (c << 6) | (bytes[pos + i] & 0x3F) as u32;
+        i += 1;
+    }
+    match char::from_u32(c) {
+        Some(c) => (c, len),
+        None => panic!("the input is a str"),
+    }
+}
+
+/// `&input[start..end]`, which has to be on character boundaries
+const fn slice(input: &str, start: usize, end: usize) -> &str {
+    input.split_at(end).0.split_at(start).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lex() {
+        let tokens = Lexer::new("1.5h 2 µs, 3.").collect::<Vec<_>>();
+        let expected = &[
+            (
+                TokenKind::Number {
+                    whole: "1",
+                    fraction: Some("5"),
+                },
+                Span::new(0, 3),
+            ),
+            (TokenKind::Word("h"), Span::new(3, 4)),
+            (TokenKind::Whitespace, Span::new(4, 5)),
+            (
+                TokenKind::Number {
+                    whole: "2",
+                    fraction: None,
+                },
+                Span::new(5, 6),
+            ),
+            (TokenKind::Whitespace, Span::new(6, 7)),
+            (TokenKind::Word("µs"), Span::new(7, 10)),
+            (TokenKind::Other(','), Span::new(10, 11)),
+            (TokenKind::Whitespace, Span::new(11, 12)),
+            (
+                TokenKind::Number {
+                    whole: "3",
+                    fraction: Some(""),
+                },
+                Span::new(12, 14),
+            ),
+        ];
+
+        assert_eq!(tokens.len(), expected.len());
+        for (token, (kind, span)) in tokens.iter().zip(expected) {
+            assert_eq!(token.kind, *kind);
+            assert_eq!(token.span, *span);
+        }
+
+        let tokens = Lexer::new("s\x0B\u{3000}éa!…m±×")
+            .map(|token| token.kind)
+            .collect::<Vec<_>>();
+        let expected = &[
+            TokenKind::Word("s"),
+            TokenKind::Whitespace,
+            TokenKind::Word("éa"),
+            TokenKind::Other('!'),
+            TokenKind::Word("…m"),
+            TokenKind::Other('±'),
+            TokenKind::Other('×'),
+        ];
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn whitespace() {
+        for c in (0..=0x10FFFF).filter_map(std::char::from_u32) {
+            assert_eq!(is_whitespace(c), c.is_whitespace(), "char: {:?}", c);
+        }
+    }
+}
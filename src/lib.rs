@@ -1,16 +1,211 @@
-#[derive(Debug, PartialEq)]
+use std::convert::TryFrom;
+
+/// `?` for `const fn`s, which can't use it
+macro_rules! try_const {
+    ($result:expr) => {
+        match $result {
+            Ok(value) => value,
+            Err(err) => return Err(err),
+        }
+    };
+}
+
+mod clock;
+mod lexer;
+use lexer::{Lexer, Token, TokenKind};
+
+mod format;
+pub use format::{format_secs, FormatOptions, HumanDuration};
+
+mod iso8601;
+pub use iso8601::{format_iso8601, parse_iso8601_nanos};
+
+mod expr;
+pub use expr::parse_expression_nanos;
+
+mod find;
+pub use find::{find_durations, DurationMatch, FindDurations};
+
+mod range;
+pub use range::{parse_range, DurationRange};
+
+mod relative;
+pub use relative::{parse_relative, Direction, Relative};
+
+mod options;
+pub use options::{DefaultUnit, ParserOptions};
+
+mod tolerance;
+pub use tolerance::{parse_tolerance, Tolerance};
+
+#[cfg(feature = "time")]
+mod calendar;
+#[cfg(feature = "time")]
+pub use calendar::{parse_calendar, Anchor, CalendarSpan};
+
+mod constant;
+pub use constant::parse_secs_const;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "clap")]
+mod clap;
+#[cfg(feature = "clap")]
+pub use self::clap::DurationValueParser;
+
+/// A byte range into the parsed input
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub const fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+impl From<Span> for std::ops::Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// An error produced while parsing, with the span of the offending input
+///
+/// ```rust
+/// use simple_duration_parse::{parse_secs, Error, Span};
+///
+/// let input = "3d 5m 2h";
+/// let err = parse_secs(input).unwrap_err();
+/// assert_eq!(
+///     err,
+///     Error::OutOfOrder {
+///         span: Span::new(6, 8),
+///         previous: Span::new(3, 5)
+///     }
+/// );
+///
+/// let span = err.span();
+/// let caret = format!("{}{}", " ".repeat(span.start), "^".repeat(span.end - span.start));
+/// assert_eq!(caret, "      ^^");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Error {
-    OutOfOrder,
-    AlreadySeen,
-    InvalidData,
+    /// A component was larger than the component before it
+    OutOfOrder {
+        span: Span,
+        previous: Span,
+    },
+    /// A component used the same unit as an earlier one
+    AlreadySeen {
+        span: Span,
+        previous: Span,
+    },
+    InvalidData {
+        span: Span,
+    },
+    /// A fraction needed more than nanosecond resolution
+    Precision {
+        span: Span,
+    },
+    Overflow {
+        span: Span,
+    },
+    /// Strict parsing found something other than whitespace between components
+    UnexpectedCharacter {
+        span: Span,
+    },
+    /// A calendar unit (years, months) has no fixed length without a date to anchor it to
+    CalendarUnit {
+        span: Span,
+    },
+    /// A clock field was too large, e.g. `1:60`
+    OutOfRange {
+        span: Span,
+    },
+    /// The duration was negative, but the target type is unsigned
+    Negative {
+        span: Span,
+    },
+}
+
+impl Error {
+    /// The span of the offending input
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::OutOfOrder { span, .. }
+            | Self::AlreadySeen { span, .. }
+            | Self::InvalidData { span }
+            | Self::Precision { span }
+            | Self::Overflow { span }
+            | Self::UnexpectedCharacter { span }
+            | Self::CalendarUnit { span }
+            | Self::OutOfRange { span }
+            | Self::Negative { span } => span,
+        }
+    }
+
+    /// The span of the earlier component this one conflicts with, if any
+    pub fn previous(&self) -> Option<Span> {
+        match *self {
+            Self::OutOfOrder { previous, .. } | Self::AlreadySeen { previous, .. } => {
+                Some(previous)
+            }
+            _ => None,
+        }
+    }
+
+    /// Move the spans `by` bytes along, for errors from a slice of the input
+    pub(crate) fn shift(mut self, by: usize) -> Self {
+        let shift = |span: &mut Span| *span = Span::new(span.start + by, span.end + by);
+        match &mut self {
+            Self::OutOfOrder { span, previous } | Self::AlreadySeen { span, previous } => {
+                shift(span);
+                shift(previous);
+            }
+            Self::InvalidData { span }
+            | Self::Precision { span }
+            | Self::Overflow { span }
+            | Self::UnexpectedCharacter { span }
+            | Self::CalendarUnit { span }
+            | Self::OutOfRange { span }
+            | Self::Negative { span } => shift(span),
+        }
+        self
+    }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::OutOfOrder => write!(f, "Out of order"),
-            Self::AlreadySeen => write!(f, "Already seen"),
-            Self::InvalidData => write!(f, "Invalid data"),
+            Self::OutOfOrder { span, previous } => {
+                write!(f, "Out of order at {} (after {})", span, previous)
+            }
+            Self::AlreadySeen { span, previous } => {
+                write!(f, "Already seen at {} (first at {})", span, previous)
+            }
+            Self::InvalidData { span } => write!(f, "Invalid data at {}", span),
+            Self::Precision { span } => {
+                write!(f, "Fraction is smaller than a nanosecond at {}", span)
+            }
+            Self::Overflow { span } => write!(f, "Overflow at {}", span),
+            Self::UnexpectedCharacter { span } => {
+                write!(f, "Unexpected character at {}", span)
+            }
+            Self::CalendarUnit { span } => {
+                write!(f, "Calendar unit without an anchor date at {}", span)
+            }
+            Self::OutOfRange { span } => write!(f, "Out of range at {}", span),
+            Self::Negative { span } => write!(f, "Negative duration at {}", span),
         }
     }
 }
@@ -24,43 +219,252 @@ impl std::error::Error for Error {}
 /// use std::time::Duration;
 ///
 /// assert_eq!(Duration::parse_human_duration("7d 3m").unwrap(), Duration::from_secs(604980));
+/// assert_eq!(Duration::parse_iso8601_duration("P7DT3M").unwrap(), Duration::from_secs(604980));
+/// ```
+pub trait DurationParser: Sized {
+    /// Construct the type from a signed count of nanoseconds parsed from the `span` of the input
+    ///
+    /// Types that already implement [`parse_human_duration`](Self::parse_human_duration)
+    /// can use [`from_nanos_via_human_duration`] for this.
+    fn from_nanos(nanos: i128, span: Span) -> Result<Self, Error>;
+
+    /// Parse a human-readable duration, which may be signed, see [`parse_signed_nanos`]
+    fn parse_human_duration(input: &str) -> Result<Self, Error> {
+        Self::from_nanos(parse_signed_nanos(input)?, Span::new(0, input.len()))
+    }
+
+    /// Parse an arithmetic expression over durations, see [`parse_expression_nanos`]
+    fn parse_expression(input: &str) -> Result<Self, Error> {
+        Self::from_nanos(parse_expression_nanos(input)?, Span::new(0, input.len()))
+    }
+
+    /// Parse an ISO 8601 duration, see [`parse_iso8601_nanos`]
+    fn parse_iso8601_duration(input: &str) -> Result<Self, Error> {
+        let nanos = i128::try_from(parse_iso8601_nanos(input)?).map_err(|_| overflow(input))?;
+        Self::from_nanos(nanos, Span::new(0, input.len()))
+    }
+}
+
+/// [`DurationParser::from_nanos`] for a type with its own [`parse_human_duration`](DurationParser::parse_human_duration)
+///
+/// This parses `{nanos}ns` with it, so it's only as precise as that. Overflow and
+/// negative errors are kept, anything else is [`Error::InvalidData`], all at `span`.
+///
+/// ```rust
+/// use simple_duration_parse::{from_nanos_via_human_duration, parse_secs, DurationParser, Error, Span};
+///
+/// struct Secs(u64);
+///
+/// impl DurationParser for Secs {
+///     fn from_nanos(nanos: i128, span: Span) -> Result<Self, Error> {
+///         from_nanos_via_human_duration(nanos, span)
+///     }
+///
+///     fn parse_human_duration(input: &str) -> Result<Self, Error> {
+///         parse_secs(input).map(Secs)
+///     }
+/// }
+///
+/// assert_eq!(Secs::parse_iso8601_duration("PT1M30S").unwrap().0, 90);
 /// ```
-pub trait DurationParser {
-    fn parse_human_duration(input: &str) -> Result<Self, Error>
-    where
-        Self: Sized;
+pub fn from_nanos_via_human_duration<T: DurationParser>(
+    nanos: i128,
+    span: Span,
+) -> Result<T, Error> {
+    T::parse_human_duration(&format!("{}ns", nanos)).map_err(|err| match err {
+        Error::Overflow { .. } => Error::Overflow { span },
+        Error::Negative { .. } => Error::Negative { span },
+        _ => Error::InvalidData { span },
+    })
 }
 
+/// Negative input returns [`Error::Negative`]
 impl DurationParser for std::time::Duration {
-    fn parse_human_duration(input: &str) -> Result<Self, Error>
-    where
-        Self: Sized,
-    {
-        let secs = parse_secs(input)?;
-        Ok(std::time::Duration::from_secs(secs))
+    fn from_nanos(nanos: i128, span: Span) -> Result<Self, Error> {
+        let nanos = u128::try_from(nanos).map_err(|_| Error::Negative { span })?;
+        let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| Error::Overflow { span })?;
+        Ok(std::time::Duration::new(secs, (nanos % NANOS_PER_SEC) as _))
     }
 }
 
 #[cfg(feature = "time")]
 impl DurationParser for time::Duration {
-    fn parse_human_duration(input: &str) -> Result<Self, Error>
-    where
-        Self: Sized,
-    {
-        let secs = parse_secs(input)?;
-        Ok(time::Duration::seconds(secs as _))
+    fn from_nanos(nanos: i128, span: Span) -> Result<Self, Error> {
+        let per_sec = NANOS_PER_SEC as i128;
+        let secs = i64::try_from(nanos / per_sec).map_err(|_| Error::Overflow { span })?;
+        Ok(time::Duration::new(secs, (nanos % per_sec) as _))
+    }
+}
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// The whole input overflowed the target type
+const fn overflow(input: &str) -> Error {
+    Error::Overflow {
+        span: Span::new(0, input.len()),
+    }
+}
+
+/// The whole input was negative, which the target type can't represent
+const fn unsigned(nanos: i128, input: &str) -> Result<u128, Error> {
+    match nanos < 0 {
+        true => Err(Error::Negative {
+            span: Span::new(0, input.len()),
+        }),
+        false => Ok(nanos as u128),
+    }
+}
+
+/// Whether the unit is months, which have no fixed length
+const fn is_month(name: &str) -> bool {
+    let mut buf = [0_u8; 12];
+    match lowercase(name, &mut buf) {
+        Some(len) => matches!(buf.split_at(len).0, b"mo" | b"mos" | b"month" | b"months"),
+        None => false,
+    }
+}
+
+/// Lowercase a unit name into a buffer that fits the longest one, so it can be
+/// matched, returning its length or `None` if it's too long
+const fn lowercase(name: &str, buf: &mut [u8; 12]) -> Option<usize> {
+    let name = name.as_bytes();
+    if name.len() > buf.len() {
+        return None;
+    }
+    let mut i = 0;
+    while i < name.len() {
+        buf[i] = name[i].to_ascii_lowercase();
+        i += 1;
+    }
+    Some(name.len())
+}
+
+/// A unit of time, ordered from smallest to largest
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Magnitude {
+    Nanosecond,
+    Microsecond,
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Year,
+}
+
+impl Magnitude {
+    /// Every magnitude, largest first
+    const DESCENDING: [Self; 9] = [
+        Self::Year,
+        Self::Week,
+        Self::Day,
+        Self::Hour,
+        Self::Minute,
+        Self::Second,
+        Self::Millisecond,
+        Self::Microsecond,
+        Self::Nanosecond,
+    ];
+
+    /// The next smaller unit, if any
+    const fn smaller(self) -> Option<Self> {
+        let smaller = match self {
+            Self::Nanosecond => return None,
+            Self::Microsecond => Self::Nanosecond,
+            Self::Millisecond => Self::Microsecond,
+            Self::Second => Self::Millisecond,
+            Self::Minute => Self::Second,
+            Self::Hour => Self::Minute,
+            Self::Day => Self::Hour,
+            Self::Week => Self::Day,
+            Self::Year => Self::Week,
+        };
+        Some(smaller)
+    }
+
+    /// Look up a unit by any of its names, ignoring case
+    const fn from_name(name: &str) -> Option<Self> {
+        let mut buf = [0_u8; 12];
+        let len = match lowercase(name, &mut buf) {
+            Some(len) => len,
+            None => return None,
+        };
+
+        let magnitude = match buf.split_at(len).0 {
+            b"ns" | b"nsec" | b"nsecs" | b"nanosecond" | b"nanoseconds" | b"nanos" => {
+                Self::Nanosecond
+            }
+            // `µs` (micro sign) and `μs` (greek small letter mu)
+            b"us" | [0xC2, 0xB5, b's'] | [0xCE, 0xBC, b's'] => Self::Microsecond,
+            b"usec" | b"usecs" | b"microsecond" | b"microseconds" | b"micros" => Self::Microsecond,
+            b"ms" | b"msec" | b"msecs" | b"millisecond" | b"milliseconds" | b"millis" => {
+                Self::Millisecond
+            }
+            b"s" | b"sec" | b"secs" | b"second" | b"seconds" => Self::Second,
+            b"m" | b"min" | b"mins" | b"minute" | b"minutes" => Self::Minute,
+            b"h" | b"hr" | b"hrs" | b"hour" | b"hours" => Self::Hour,
+            b"d" | b"day" | b"days" => Self::Day,
+            b"w" | b"wk" | b"wks" | b"week" | b"weeks" => Self::Week,
+            b"y" | b"yr" | b"yrs" | b"year" | b"years" => Self::Year,
+            _ => return None,
+        };
+        Some(magnitude)
+    }
+
+    const fn to_nanos(self) -> u128 {
+        match self {
+            Self::Nanosecond => 1,
+            Self::Microsecond => 1_000,
+            Self::Millisecond => 1_000_000,
+            Self::Second => NANOS_PER_SEC,
+            Self::Minute => 60 * NANOS_PER_SEC,
+            Self::Hour => 60 * 60 * NANOS_PER_SEC,
+            Self::Day => 60 * 60 * 24 * NANOS_PER_SEC,
+            Self::Week => 60 * 60 * 24 * 7 * NANOS_PER_SEC,
+            Self::Year => 60 * 60 * 24 * 365 * NANOS_PER_SEC,
+        }
+    }
+
+    /// The canonical suffix, used when formatting
+    fn suffix(self) -> &'static str {
+        match self {
+            Self::Nanosecond => "ns",
+            Self::Microsecond => "us",
+            Self::Millisecond => "ms",
+            Self::Second => "s",
+            Self::Minute => "m",
+            Self::Hour => "h",
+            Self::Day => "d",
+            Self::Week => "w",
+            Self::Year => "y",
+        }
     }
 }
 
 /// Parse the input string into seconds
 ///
+/// Units are case-insensitive and can be separated from their quantity by whitespace
+/// (e.g. `3 hours 5 mins`). Each quantity can have a decimal fraction (e.g. `1.5h`),
+/// which is computed exactly.
+///
+/// Clock-style components, `[d.]HH:MM:SS[.f]` or `MM:SS[.f]`, take the place of hours,
+/// minutes and seconds (e.g. `1:02:03`, `2d 03:00:00` or `2.03:00:00`). Fields after
+/// something larger must be in range, so `1:60` returns [`Error::OutOfRange`].
+/// Any sub-second components are truncated, use [`parse_nanos`] to keep them.
+///
 /// # Format:
 /// | suffix | description |
 /// | -- | -- |
-/// | d | days |
-/// | h | hours |
-/// | m | minutes |
-/// | s | seconds |
+/// | y, yr, yrs, year, years | years (always 365 days) |
+/// | w, wk, wks, week, weeks | weeks |
+/// | d, day, days | days |
+/// | h, hr, hrs, hour, hours | hours |
+/// | m, min, mins, minute, minutes | minutes |
+/// | s, sec, secs, second, seconds | seconds |
+/// | ms, msec, msecs, millis, millisecond, milliseconds | milliseconds |
+/// | us, µs, usec, usecs, micros, microsecond, microseconds | microseconds |
+/// | ns, nsec, nsecs, nanos, nanosecond, nanoseconds | nanoseconds |
 ///
 /// ```rust
 /// let tests = &[
@@ -73,104 +477,392 @@ impl DurationParser for time::Duration {
 ///     ("7d", (60 * 60 * 24 * 7)),
 ///     ("3d 5m", (60 * 60 * 24 * 3) + 5 * 60),
 ///     ("1s foobar", 1),
+///     ("1s 500ms", 1),
+///     ("2w", (60 * 60 * 24 * 14)),
+///     ("1y 1d", (60 * 60 * 24 * 366)),
+///     ("1.5h", (60 * 60) + 30 * 60),
+///     ("0.25d", (60 * 60 * 6)),
+///     ("3 hours 5 minutes", (60 * 60 * 3) + 5 * 60),
+///     ("1 Day 4 hrs", (60 * 60 * 28)),
+///     ("1:02:03", (60 * 60) + (2 * 60) + 3),
+///     ("2d 03:00:00", (60 * 60 * 51)),
 /// ];
 ///
 /// for (input, expected) in tests {
-///     assert_eq!(simple_duration_parse::parse_secs(&input).unwrap(), *expected);
+///     assert_eq!(simple_duration_parse::parse_secs(input).unwrap(), *expected);
 /// }
 /// ```
 pub fn parse_secs(input: &str) -> Result<u64, Error> {
-    #[derive(Default)]
-    struct Buf(Vec<char>);
-    impl Buf {
-        fn is_empty(&self) -> bool {
-            self.0.is_empty()
-        }
-        fn append(&mut self, ch: char) {
-            self.0.push(ch)
-        }
-        fn parse(&mut self, magnitude: Magnitude) -> Option<u64> {
-            if self.is_empty() {
-                return None;
-            }
+    ParserOptions::new().parse_secs(input)
+}
+
+/// Parse the input string into seconds, rejecting anything unrecognized
+///
+/// This accepts the same format as [`parse_secs`], but only whitespace is
+/// allowed between components and the input must not be empty.
+///
+/// ```rust
+/// use simple_duration_parse::{parse_secs_strict, Error, Span};
+///
+/// assert_eq!(parse_secs_strict("1h 30m").unwrap(), 5400);
+/// assert_eq!(parse_secs_strict(" 1h30m ").unwrap(), 5400);
+/// assert_eq!(
+///     parse_secs_strict("1s foobar").unwrap_err(),
+///     Error::UnexpectedCharacter { span: Span::new(3, 4) }
+/// );
+/// ```
+pub fn parse_secs_strict(input: &str) -> Result<u64, Error> {
+    ParserOptions::new().strict(true).parse_secs(input)
+}
+
+/// Parse the input string into nanoseconds
+///
+/// This accepts the same format as [`parse_secs`]. A fraction that would need
+/// finer than nanosecond resolution returns [`Error::Precision`]
+///
+/// ```rust
+/// let tests = &[
+///     ("1s", 1_000_000_000),
+///     ("250ms", 250_000_000),
+///     ("1s 500ms", 1_500_000_000),
+///     ("3us 20ns", 3_020),
+///     ("3µs", 3_000),
+///     ("1.5s", 1_500_000_000),
+/// ];
+///
+/// for (input, expected) in tests {
+///     assert_eq!(simple_duration_parse::parse_nanos(&input).unwrap(), *expected);
+/// }
+/// ```
+pub fn parse_nanos(input: &str) -> Result<u128, Error> {
+    ParserOptions::new().parse_nanos(input)
+}
+
+/// Parse the input string into nanoseconds, rejecting anything unrecognized
+///
+/// This is the strict form of [`parse_nanos`], see [`parse_secs_strict`]
+pub fn parse_nanos_strict(input: &str) -> Result<u128, Error> {
+    ParserOptions::new().strict(true).parse_nanos(input)
+}
+
+/// Parse the input string into signed nanoseconds
+///
+/// A leading `+` or `-` on the first component applies to the whole expression,
+/// and to every later component without a sign of its own. A sign on a later
+/// component only applies to that component. A sign that isn't directly followed
+/// by a number, like the one in `1h - 30m`, is [`Error::InvalidData`].
+///
+/// The unsigned parsers return [`Error::Negative`] for negative input.
+///
+/// ```rust
+/// use simple_duration_parse::parse_signed_nanos;
+///
+/// const MIN: i128 = 60 * 1_000_000_000;
+///
+/// let tests = &[
+///     ("-5m", -5 * MIN),
+///     ("-1h 30m", -90 * MIN),
+///     ("1h -5m", 55 * MIN),
+///     ("-1h +5m", -55 * MIN),
+///     ("+2m", 2 * MIN),
+/// ];
+///
+/// for (input, expected) in tests {
+///     assert_eq!(parse_signed_nanos(input).unwrap(), *expected);
+/// }
+/// ```
+pub fn parse_signed_nanos(input: &str) -> Result<i128, Error> {
+    ParserOptions::new().parse_signed_nanos(input)
+}
+
+/// Parse the input string into signed seconds, see [`parse_signed_nanos`]
+///
+/// Any sub-second components are truncated towards zero.
+pub fn parse_signed_secs(input: &str) -> Result<i64, Error> {
+    ParserOptions::new().parse_signed_secs(input)
+}
 
-            Some(
-                self.0
-                    .drain(..)
-                    .filter_map(|c| c.to_digit(10).map(u64::from))
-                    .fold(0, |a, c| 10 * a + c)
-                    * magnitude.to_secs(),
-            )
+/// Tracks the components seen so far, enforcing the order and duplicate options
+struct Order<'a> {
+    options: &'a ParserOptions,
+    previous: Option<(Magnitude, Span)>,
+    seen: [Option<Span>; Magnitude::DESCENDING.len()],
+    end: Option<usize>,
+}
+
+impl<'a> Order<'a> {
+    const fn new(options: &'a ParserOptions) -> Self {
+        Self {
+            options,
+            previous: None,
+            seen: [None; Magnitude::DESCENDING.len()],
+            end: None,
         }
     }
 
-    #[derive(Default)]
-    struct Order(Option<Magnitude>);
-    impl Order {
-        fn verify(&mut self, magnitude: Magnitude) -> Result<Magnitude, Error> {
-            match self.0 {
-                Some(a) if a > magnitude => self.0.replace(magnitude),
-                Some(a) if a == magnitude => return Err(Error::AlreadySeen),
-                None => self.0.replace(magnitude),
-                _ => return Err(Error::OutOfOrder),
-            };
-            Ok(magnitude)
-        }
+    const fn is_empty(&self) -> bool {
+        self.previous.is_none()
     }
 
-    #[derive(Copy, Clone, PartialEq, PartialOrd)]
-    enum Magnitude {
-        Second,
-        Minute,
-        Hour,
-        Day,
+    const fn verify(&mut self, magnitude: Magnitude, span: Span) -> Result<(), Error> {
+        match self.previous {
+            Some((a, previous)) if self.options.enforce_order && (a as u8) < magnitude as u8 => {
+                return Err(Error::OutOfOrder { span, previous })
+            }
+            _ => {}
+        }
+        match self.seen[magnitude as usize] {
+            Some(previous) if !self.options.allow_duplicates => {
+                return Err(Error::AlreadySeen { span, previous })
+            }
+            Some(..) => {}
+            None => self.seen[magnitude as usize] = Some(span),
+        }
+        self.previous = Some((magnitude, span));
+        Ok(())
     }
-    impl Magnitude {
-        fn to_secs(self) -> u64 {
-            match self {
-                Self::Second => 1,
-                Self::Minute => 60,
-                Self::Hour => 60 * 60,
-                Self::Day => 60 * 60 * 24,
+
+    /// Verify that the component at `span` doesn't touch the previous one
+    const fn separated(&mut self, span: Span) -> Result<(), Error> {
+        match self.end.replace(span.end) {
+            Some(end) if self.options.require_whitespace && end == span.start => {
+                Err(Error::InvalidData { span })
             }
+            _ => Ok(()),
         }
     }
+}
 
-    let (mut order, mut buf): (Order, Buf) = Default::default();
-    let mut iter = input.chars().peekable();
-    let mut acc = 0;
+const fn parse(input: &str, options: &ParserOptions) -> Result<i128, Error> {
+    let strict = options.strict;
+    let mut order = Order::new(options);
+    let mut tokens = Lexer::new(input);
+    let mut acc: i128 = 0;
+    let mut negative = false;
 
-    macro_rules! verify {
-        ($mag:expr) => {{
-            if buf.is_empty() {
-                return Err(Error::InvalidData);
+    while let Some(mut token) = tokens.next_token() {
+        let start = token.span.start;
+        let sign = match token.kind {
+            TokenKind::Other(sign @ '+') | TokenKind::Other(sign @ '-')
+                if matches!(tokens.peek_byte(), Some(b'0'..=b'9')) =>
+            {
+                token = tokens.next_token().unwrap();
+                Some(sign == '-')
             }
-            match buf.parse(order.verify($mag)?) {
-                Some(d) => d,
-                None => break,
+            // a sign that isn't attached to a number would silently be dropped otherwise
+            TokenKind::Other('+') | TokenKind::Other('-') => {
+                return Err(Error::InvalidData { span: token.span })
             }
-        }};
-    }
+            _ => None,
+        };
+
+        let (whole, fraction) = match token.kind {
+            TokenKind::Number { whole, fraction } => (whole, fraction),
+            TokenKind::Word(word) if Magnitude::from_name(word).is_some() => {
+                return Err(Error::InvalidData { span: token.span })
+            }
+            TokenKind::Whitespace => continue,
+            _ if !strict => continue,
+            _ => {
+                let (_, len) = lexer::decode(input.as_bytes(), token.span.start);
+                return Err(Error::UnexpectedCharacter {
+                    span: Span::new(token.span.start, token.span.start + len),
+                });
+            }
+        };
 
-    while let Some(left) = iter.next() {
-        acc += match (left, iter.peek()) {
-            ('s', ..) => verify!(Magnitude::Second),
-            ('m', ..) => verify!(Magnitude::Minute),
-            ('h', ..) => verify!(Magnitude::Hour),
-            ('d', ..) => verify!(Magnitude::Day),
-            (c, Some(..)) if c.is_ascii_digit() => {
-                if buf.is_empty() && c == '0' {
-                    return Err(Error::InvalidData);
+        if matches!(tokens.peek_byte(), Some(b':')) {
+            let first = order.is_empty();
+            let (nanos, span) = try_const!(clock::parse_clock(token, &mut tokens, &mut order));
+            let span = Span::new(start, span.end);
+            try_const!(order.separated(span));
+            let negative = signed(sign, first, &mut negative);
+            acc = try_const!(accumulate(acc, nanos, negative, span));
+            continue;
+        }
+
+        tokens.skip_whitespace();
+
+        let (magnitude, end) = match tokens.next_token() {
+            Some(Token {
+                kind: TokenKind::Word(word),
+                span,
+            }) => match Magnitude::from_name(word) {
+                Some(magnitude) => (magnitude, span.end),
+                None if is_month(word) => {
+                    return Err(Error::CalendarUnit {
+                        span: Span::new(token.span.start, span.end),
+                    })
                 }
-                buf.append(c);
-                continue;
+                None => return Err(Error::InvalidData { span: token.span }),
+            },
+            Some(Token {
+                kind: TokenKind::Other('.'),
+                span,
+            }) if span.start == token.span.end => {
+                return Err(Error::InvalidData {
+                    span: Span::new(token.span.start, span.end),
+                })
             }
-            (c, None) if c.is_ascii_digit() => return Err(Error::InvalidData),
-            _ => continue,
+            None => match options.default_unit {
+                Some(DefaultUnit::Fixed(magnitude)) => (magnitude, token.span.end),
+                Some(DefaultUnit::NextSmaller) => match order.previous {
+                    Some((previous, _)) => match previous.smaller() {
+                        Some(magnitude) => (magnitude, token.span.end),
+                        None => return Err(Error::InvalidData { span: token.span }),
+                    },
+                    None => (Magnitude::Second, token.span.end),
+                },
+                None => return Err(Error::InvalidData { span: token.span }),
+            },
+            _ => return Err(Error::InvalidData { span: token.span }),
+        };
+
+        let first = order.is_empty();
+        let span = Span::new(start, end);
+        try_const!(order.separated(span));
+        try_const!(order.verify(magnitude, span));
+        let quantity = Span::new(token.span.start, end);
+        try_const!(check_quantity(whole, fraction, quantity, options));
+        let fraction = match fraction {
+            Some(fraction) => fraction,
+            None => "",
+        };
+        let nanos = match try_const!(scale(whole, fraction, magnitude, span)) {
+            0 if !options.allow_zero => return Err(Error::InvalidData { span }),
+            nanos => nanos,
+        };
+        let negative = signed(sign, first, &mut negative);
+        acc = try_const!(accumulate(acc, nanos, negative, span));
+    }
+
+    if strict && order.is_empty() {
+        return Err(Error::InvalidData {
+            span: Span::new(0, input.len()),
+        });
+    }
+
+    Ok(acc)
+}
+
+/// Whether a component is negative, the first component's sign carries over to the rest
+const fn signed(sign: Option<bool>, first: bool, negative: &mut bool) -> bool {
+    match sign {
+        Some(sign) if first => {
+            *negative = sign;
+            sign
         }
+        Some(sign) => sign,
+        None => *negative,
+    }
+}
+
+const fn accumulate(acc: i128, nanos: u128, negative: bool, span: Span) -> Result<i128, Error> {
+    if nanos > i128::MAX as u128 {
+        return Err(Error::Overflow { span });
+    }
+    let acc = match negative {
+        true => acc.checked_sub(nanos as i128),
+        false => acc.checked_add(nanos as i128),
+    };
+    match acc {
+        Some(acc) => Ok(acc),
+        None => Err(Error::Overflow { span }),
+    }
+}
+
+/// Reject empty fractions (`1.`) and, unless allowed, leading zeros (`06`, `0`)
+const fn check_quantity(
+    whole: &str,
+    fraction: Option<&str>,
+    span: Span,
+    options: &ParserOptions,
+) -> Result<(), Error> {
+    let leading_zero = Error::InvalidData {
+        span: Span::new(span.start, span.start + 1),
+    };
+    let whole = whole.as_bytes();
+    match fraction {
+        Some(fraction) if fraction.is_empty() => Err(Error::InvalidData {
+            span: Span::new(span.start, span.start + whole.len() + 1),
+        }),
+        None if whole.len() == 1 && whole[0] == b'0' && !options.allow_zero => Err(leading_zero),
+        _ if whole.len() > 1 && whole[0] == b'0' && !options.allow_leading_zeros => {
+            Err(leading_zero)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Scale a `whole.fraction` quantity of `magnitude` into nanoseconds
+const fn scale(
+    whole: &str,
+    fraction: &str,
+    magnitude: Magnitude,
+    span: Span,
+) -> Result<u128, Error> {
+    let overflow = Error::Overflow { span };
+    let (whole, fraction) = (whole.as_bytes(), fraction.as_bytes());
+
+    // trailing zeros don't change the fraction, but would needlessly grow the scale
+    let mut len = fraction.len();
+    while len > 0 && fraction[len - 1] == b'0' {
+        len -= 1;
+    }
+
+    let (mut acc, mut i) = (0_u128, 0);
+    while i < whole.len() {
+        acc = match push_digit(acc, whole[i]) {
+            Some(acc) => acc,
+            None => return Err(overflow),
+        };
+        i += 1;
+    }
+
+    let (mut frac, mut scale, mut i) = (0_u128, 1_u128, 0);
+    while i < len {
+        frac = match push_digit(frac, fraction[i]) {
+            Some(frac) => frac,
+            None => return Err(overflow),
+        };
+        scale = match scale.checked_mul(10) {
+            Some(scale) => scale,
+            None => return Err(overflow),
+        };
+        i += 1;
+    }
+
+    let unit = magnitude.to_nanos();
+    // 128-bit division is slow, so skip it for whole quantities
+    if len == 0 {
+        return match acc.checked_mul(unit) {
+            Some(nanos) => Ok(nanos),
+            None => Err(overflow),
+        };
+    }
+
+    let frac = match frac.checked_mul(unit) {
+        Some(frac) => frac,
+        None => return Err(overflow),
+    };
+    if frac % scale != 0 {
+        return Err(Error::Precision { span });
     }
+    match acc.checked_mul(unit) {
+        Some(whole) => match whole.checked_add(frac / scale) {
+            Some(nanos) => Ok(nanos),
+            None => Err(overflow),
+        },
+        None => Err(overflow),
+    }
+}
 
-    return Ok(acc);
+/// Shift an ASCII digit onto the end of `acc`
+const fn push_digit(acc: u128, digit: u8) -> Option<u128> {
+    match acc.checked_mul(10) {
+        Some(acc) => acc.checked_add((digit - b'0') as u128),
+        None => None,
+    }
 }
 
 #[cfg(test)]
@@ -190,31 +882,557 @@ mod tests {
             ("1s foobar", 1),
             ("foobar 1s", 1),
             ("1m 58794384s", 60 + 58794384),
+            ("1s 500ms", 1),
+            ("2w", 60 * 60 * 24 * 14),
+            ("1y", 60 * 60 * 24 * 365),
+            ("1y 2w 3d", 60 * 60 * 24 * (365 + 14 + 3)),
+            ("1.5h", 60 * 60 + 30 * 60),
+            ("0.25d", 60 * 60 * 6),
+            ("1.5s", 1),
+            ("1s foobar.", 1),
+            ("18446744073709551615s", u64::MAX),
+            ("307445734561825860m 15s", u64::MAX),
+            ("213503982334601d 7h 15s", u64::MAX),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_secs(input).unwrap(), *expected, "input: {}", input);
+        }
+
+        let tests = &[
+            (
+                "1s 1m",
+                Error::OutOfOrder {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                "1s 1s",
+                Error::AlreadySeen {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                "0s",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "06s",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1m 1",
+                Error::InvalidData {
+                    span: Span::new(3, 4),
+                },
+            ),
+            (
+                "1s1",
+                Error::InvalidData {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "1ms 1s",
+                Error::OutOfOrder {
+                    span: Span::new(4, 6),
+                    previous: Span::new(0, 3),
+                },
+            ),
+            (
+                "1ms 1ms",
+                Error::AlreadySeen {
+                    span: Span::new(4, 7),
+                    previous: Span::new(0, 3),
+                },
+            ),
+            (
+                "ms",
+                Error::InvalidData {
+                    span: Span::new(0, 2),
+                },
+            ),
+            (
+                "1d 1w",
+                Error::OutOfOrder {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                "1w 1y",
+                Error::OutOfOrder {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                "1y 1y",
+                Error::AlreadySeen {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                "1.h",
+                Error::InvalidData {
+                    span: Span::new(0, 2),
+                },
+            ),
+            (
+                "1.5.5h",
+                Error::InvalidData {
+                    span: Span::new(0, 4),
+                },
+            ),
+            (
+                "00.5h",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "0.0s",
+                Error::InvalidData {
+                    span: Span::new(0, 4),
+                },
+            ),
+            (
+                "18446744073709551616s",
+                Error::Overflow {
+                    span: Span::new(0, 21),
+                },
+            ),
+            (
+                "307445734561825860m 16s",
+                Error::Overflow {
+                    span: Span::new(0, 23),
+                },
+            ),
+            (
+                "213503982334601d 7h 16s",
+                Error::Overflow {
+                    span: Span::new(0, 23),
+                },
+            ),
+            (
+                "1000000000000000000000000000000000000000s",
+                Error::Overflow {
+                    span: Span::new(0, 41),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_secs(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_nanos_test() {
+        let tests = &[
+            ("1ns", 1),
+            ("1us", 1_000),
+            ("1µs", 1_000),
+            ("1ms", 1_000_000),
+            ("1s 500ms", 1_500_000_000),
+            ("1m 1ms 1us 1ns", 60_001_001_001),
+            ("250ms", 250_000_000),
+            ("1.5ms", 1_500_000),
+            ("0.000000001s", 1),
+            ("1.000000001s", 1_000_000_001),
+            ("0.1us", 100),
+            ("0.333h", 1_198_800_000_000),
+            (
+                "1.50000000000000000000000000000000000000000s",
+                1_500_000_000,
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_nanos(input).unwrap(), *expected, "input: {}", input);
+        }
+
+        let tests = &[
+            (
+                "1.5ns",
+                Error::Precision {
+                    span: Span::new(0, 5),
+                },
+            ),
+            (
+                "0.0000000001s",
+                Error::Precision {
+                    span: Span::new(0, 13),
+                },
+            ),
+            (
+                "1.0001us",
+                Error::Precision {
+                    span: Span::new(0, 8),
+                },
+            ),
+            (
+                "1000000000000000000000000000000y",
+                Error::Overflow {
+                    span: Span::new(0, 32),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_nanos(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_unit_names_test() {
+        let tests = &[
+            ("2 hours", 2 * 60 * 60),
+            ("3 hours 5 minutes", (3 * 60 * 60) + 5 * 60),
+            ("1 day 4 hrs", (60 * 60 * 24) + 4 * 60 * 60),
+            ("1day 4hrs 10mins", (60 * 60 * 24) + (4 * 60 * 60) + 10 * 60),
+            ("1 Hour 1 MIN 1 Sec", (60 * 60) + 60 + 1),
+            ("1 year 2 days", 60 * 60 * 24 * 367),
+            ("1 hr, 30 seconds", (60 * 60) + 30),
+            ("90 secs", 90),
+            ("1.5 hours", (60 * 60) + 30 * 60),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_secs(input).unwrap(), *expected, "input: {}", input);
+        }
+
+        let tests = &[
+            ("1 ms 2 nsecs", 1_000_002),
+            ("3 microseconds 4 nanoseconds", 3_004),
+            ("5 Millis", 5_000_000),
+            ("5 MS", 5_000_000),
+            ("5 µS", 5_000),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(parse_nanos(input).unwrap(), *expected, "input: {}", input);
+        }
+
+        let tests = &[
+            (
+                "2 weeks 1 wk",
+                Error::AlreadySeen {
+                    span: Span::new(8, 12),
+                    previous: Span::new(0, 7),
+                },
+            ),
+            (
+                "2 hours 1 hour",
+                Error::AlreadySeen {
+                    span: Span::new(8, 14),
+                    previous: Span::new(0, 7),
+                },
+            ),
+            (
+                "1 min 2 hours",
+                Error::OutOfOrder {
+                    span: Span::new(6, 13),
+                    previous: Span::new(0, 5),
+                },
+            ),
+            (
+                "1 fortnight",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "hours",
+                Error::InvalidData {
+                    span: Span::new(0, 5),
+                },
+            ),
+            (
+                "1 year 2 months",
+                Error::CalendarUnit {
+                    span: Span::new(7, 15),
+                },
+            ),
+            (
+                "3mo",
+                Error::CalendarUnit {
+                    span: Span::new(0, 3),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_secs(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_strict_test() {
+        let tests = &[
+            ("1s", 1),
+            ("1h 1m 1s", (60 * 60) + 60 + 1),
+            ("1h1m1s", (60 * 60) + 60 + 1),
+            ("  1h\t1m\n1s  ", (60 * 60) + 60 + 1),
+            ("1.5h 500ms", (60 * 60) + 30 * 60),
+            ("1 sec", 1),
+            ("1sec", 1),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_secs_strict(input).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let tests = &[
+            (
+                "1s foobar",
+                Error::UnexpectedCharacter {
+                    span: Span::new(3, 4),
+                },
+            ),
+            (
+                "foobar 1s",
+                Error::UnexpectedCharacter {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1 sec later",
+                Error::UnexpectedCharacter {
+                    span: Span::new(6, 7),
+                },
+            ),
+            (
+                "1 fortnight",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                "1s, 2m",
+                Error::UnexpectedCharacter {
+                    span: Span::new(2, 3),
+                },
+            ),
+            (
+                "1.",
+                Error::InvalidData {
+                    span: Span::new(0, 2),
+                },
+            ),
+            (
+                "1s é",
+                Error::UnexpectedCharacter {
+                    span: Span::new(3, 5),
+                },
+            ),
+            (
+                "",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "   ",
+                Error::InvalidData {
+                    span: Span::new(0, 3),
+                },
+            ),
+            (
+                "1s 1m",
+                Error::OutOfOrder {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_secs_strict(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn std_duration_bounds() {
+        use std::time::Duration;
+        assert_eq!(
+            Duration::parse_human_duration("18446744073709551615s 999999999ns").unwrap(),
+            Duration::new(u64::MAX, 999_999_999)
+        );
+        assert_eq!(
+            Duration::parse_human_duration("18446744073709551616s").unwrap_err(),
+            Error::Overflow {
+                span: Span::new(0, 21)
+            }
+        );
+    }
+
+    #[test]
+    fn from_nanos_via_human_duration_test() {
+        // implemented the way it was before `from_nanos` existed
+        #[derive(Debug, PartialEq)]
+        struct Secs(u64);
+
+        impl DurationParser for Secs {
+            fn from_nanos(nanos: i128, span: Span) -> Result<Self, Error> {
+                from_nanos_via_human_duration(nanos, span)
+            }
+
+            fn parse_human_duration(input: &str) -> Result<Self, Error> {
+                parse_secs(input).map(Secs)
+            }
+        }
+
+        assert_eq!(Secs::parse_human_duration("1m 30s").unwrap(), Secs(90));
+        assert_eq!(Secs::parse_iso8601_duration("PT1M30S").unwrap(), Secs(90));
+        assert_eq!(Secs::parse_expression("2 * 45s").unwrap(), Secs(90));
+        assert_eq!(
+            Secs::parse_expression("1m - 2m").unwrap_err(),
+            Error::Negative {
+                span: Span::new(0, 7)
+            }
+        );
+    }
+
+    #[test]
+    fn parse_signed_test() {
+        const MIN: i128 = 60 * NANOS_PER_SEC as i128;
+        let tests = &[
+            ("5m", 5 * MIN),
+            ("+5m", 5 * MIN),
+            ("-5m", -5 * MIN),
+            ("-1h 30m", -90 * MIN),
+            ("-1h -30m", -90 * MIN),
+            ("-1h +30m", -30 * MIN),
+            ("1h -30m", 30 * MIN),
+            ("-1:30", -(MIN + 30 * MIN / 60)),
+            ("-1.5m", -(3 * MIN / 2)),
         ];
 
         for (input, expected) in tests {
-            assert_eq!(parse_secs(&input).unwrap(), *expected, "input: {}", input);
+            assert_eq!(
+                parse_signed_nanos(input).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
         }
 
+        assert_eq!(parse_signed_secs("-1500ms").unwrap(), -1);
+        assert_eq!(parse_signed_secs("-1h 1ms").unwrap(), -3600);
+
         let tests = &[
-            ("1s 1m", Error::OutOfOrder),
-            ("1s 1s", Error::AlreadySeen),
-            ("0s", Error::InvalidData),
-            ("06s", Error::InvalidData),
-            ("1m 1", Error::InvalidData),
-            ("1s1", Error::InvalidData),
+            (
+                "-5m",
+                Error::Negative {
+                    span: Span::new(0, 3),
+                },
+            ),
+            (
+                "-1h +30m",
+                Error::Negative {
+                    span: Span::new(0, 8),
+                },
+            ),
         ];
 
         for (input, expected) in tests {
             assert_eq!(
-                parse_secs(&input).unwrap_err(),
+                parse_secs(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+            assert_eq!(
+                parse_nanos_strict(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+            assert_eq!(
+                std::time::Duration::parse_human_duration(input).unwrap_err(),
                 *expected,
                 "input: {}",
                 input
             );
         }
+
+        assert_eq!(
+            parse_signed_nanos("1h - 30m").unwrap_err(),
+            Error::InvalidData {
+                span: Span::new(3, 4),
+            }
+        );
+        assert_eq!(
+            parse_signed_nanos("-5m 1h").unwrap_err(),
+            Error::OutOfOrder {
+                span: Span::new(4, 6),
+                previous: Span::new(0, 3),
+            }
+        );
+        assert_eq!(
+            parse_signed_nanos("-0s").unwrap_err(),
+            Error::InvalidData {
+                span: Span::new(1, 2),
+            }
+        );
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_duration_bounds() {
+        assert_eq!(
+            time::Duration::parse_human_duration("9223372036854775807s").unwrap(),
+            time::Duration::seconds(i64::MAX)
+        );
+        assert_eq!(
+            time::Duration::parse_human_duration("9223372036854775808s").unwrap_err(),
+            Error::Overflow {
+                span: Span::new(0, 20)
+            }
+        );
+        assert_eq!(
+            time::Duration::parse_human_duration("-9223372036854775808s").unwrap(),
+            time::Duration::seconds(i64::MIN)
+        );
+        assert_eq!(
+            time::Duration::parse_human_duration("-1m 30s 500ms").unwrap(),
+            time::Duration::milliseconds(-90_500)
+        );
     }
 }
 
-#[cfg(all(doctest))]
+#[cfg(doctest)]
 doc_comment::doctest!("../README.md");
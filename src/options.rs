@@ -0,0 +1,339 @@
+use crate::{overflow, parse, unsigned, DurationParser, Error, Magnitude, Span, NANOS_PER_SEC};
+use std::convert::TryFrom;
+
+/// The unit of a trailing number without one, see [`ParserOptions::default_unit`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DefaultUnit {
+    /// Always use this unit, e.g. seconds for `30`
+    Fixed(Magnitude),
+    /// Use the unit after the previous component's, so `1m 30` is `1m 30s`
+    ///
+    /// A lone number is in seconds
+    NextSmaller,
+}
+
+/// Options for how strictly the input is parsed
+///
+/// The default is what [`parse_secs`](crate::parse_secs) and friends use:
+///
+/// ```rust
+/// use simple_duration_parse::{Error, ParserOptions, Span};
+///
+/// let options = ParserOptions::new();
+/// assert_eq!(
+///     options.parse_secs("1s 1m").unwrap_err(),
+///     Error::OutOfOrder { span: Span::new(3, 5), previous: Span::new(0, 2) }
+/// );
+///
+/// let options = ParserOptions::new().enforce_order(false).allow_duplicates(true);
+/// assert_eq!(options.parse_secs("1s 1m 1s").unwrap(), 62);
+///
+/// let options = ParserOptions::new().allow_zero(true).allow_leading_zeros(true);
+/// assert_eq!(options.parse_secs("0h 05m").unwrap(), 300);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ParserOptions {
+    pub(crate) enforce_order: bool,
+    pub(crate) allow_duplicates: bool,
+    pub(crate) allow_zero: bool,
+    pub(crate) allow_leading_zeros: bool,
+    pub(crate) strict: bool,
+    pub(crate) require_whitespace: bool,
+    pub(crate) default_unit: Option<DefaultUnit>,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParserOptions {
+    pub const fn new() -> Self {
+        Self {
+            enforce_order: true,
+            allow_duplicates: false,
+            allow_zero: false,
+            allow_leading_zeros: false,
+            strict: false,
+            require_whitespace: false,
+            default_unit: None,
+        }
+    }
+
+    /// Whether components must go from largest to smallest unit
+    ///
+    /// When enabled, a larger unit after a smaller one returns [`Error::OutOfOrder`]
+    pub const fn enforce_order(mut self, enforce: bool) -> Self {
+        self.enforce_order = enforce;
+        self
+    }
+
+    /// Whether a unit can appear more than once, in which case the quantities are summed
+    ///
+    /// When disabled, a repeated unit returns [`Error::AlreadySeen`]
+    pub const fn allow_duplicates(mut self, allow: bool) -> Self {
+        self.allow_duplicates = allow;
+        self
+    }
+
+    /// Whether zero quantities, e.g. `0s`, are allowed
+    pub const fn allow_zero(mut self, allow: bool) -> Self {
+        self.allow_zero = allow;
+        self
+    }
+
+    /// Whether quantities can have leading zeros, e.g. `05m`
+    pub const fn allow_leading_zeros(mut self, allow: bool) -> Self {
+        self.allow_leading_zeros = allow;
+        self
+    }
+
+    /// Whether to reject anything other than whitespace between components, and empty input
+    ///
+    /// See [`parse_secs_strict`](crate::parse_secs_strict)
+    pub const fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Whether components must be separated by whitespace, rejecting `1h30m`
+    pub const fn require_whitespace(mut self, require: bool) -> Self {
+        self.require_whitespace = require;
+        self
+    }
+
+    /// The unit for a trailing number without one, e.g. the `30` in `1m 30`
+    ///
+    /// Without a default unit, the number returns [`Error::InvalidData`]
+    pub const fn default_unit(mut self, unit: DefaultUnit) -> Self {
+        self.default_unit = Some(unit);
+        self
+    }
+
+    /// Parse the input string into seconds, truncating any sub-second components
+    ///
+    /// See [`parse_secs`](crate::parse_secs) for the format
+    pub const fn parse_secs(&self, input: &str) -> Result<u64, Error> {
+        let nanos = try_const!(self.parse_nanos(input));
+        // 128-bit division is slow, and nearly every duration fits in 64 bits
+        if nanos <= u64::MAX as u128 {
+            return Ok(nanos as u64 / NANOS_PER_SEC as u64);
+        }
+        match nanos / NANOS_PER_SEC {
+            secs if secs <= u64::MAX as u128 => Ok(secs as u64),
+            _ => Err(overflow(input)),
+        }
+    }
+
+    /// Parse the input string into nanoseconds
+    pub const fn parse_nanos(&self, input: &str) -> Result<u128, Error> {
+        unsigned(try_const!(parse(input, self)), input)
+    }
+
+    /// Parse the input string into signed seconds, truncating towards zero
+    pub fn parse_signed_secs(&self, input: &str) -> Result<i64, Error> {
+        let nanos = self.parse_signed_nanos(input)?;
+        i64::try_from(nanos / NANOS_PER_SEC as i128).map_err(|_| overflow(input))
+    }
+
+    /// Parse the input string into signed nanoseconds
+    ///
+    /// See [`parse_signed_nanos`](crate::parse_signed_nanos) for how signs are handled
+    pub const fn parse_signed_nanos(&self, input: &str) -> Result<i128, Error> {
+        parse(input, self)
+    }
+
+    /// Parse the input string into a type
+    pub fn parse<T: DurationParser>(&self, input: &str) -> Result<T, Error> {
+        T::from_nanos(self.parse_signed_nanos(input)?, Span::new(0, input.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options() {
+        let tests = &[
+            (ParserOptions::new().enforce_order(false), "1s 1m", 61),
+            (ParserOptions::new().enforce_order(false), "1s 1h 1m", 3661),
+            (ParserOptions::new().allow_duplicates(true), "1m 1m", 120),
+            (
+                ParserOptions::new().allow_duplicates(true),
+                "1h 1m 1m 1s",
+                3721,
+            ),
+            (
+                ParserOptions::new()
+                    .enforce_order(false)
+                    .allow_duplicates(true),
+                "1s 1m 1s",
+                62,
+            ),
+            (ParserOptions::new().allow_zero(true), "0s", 0),
+            (ParserOptions::new().allow_zero(true), "1h 0m 5s", 3605),
+            (ParserOptions::new().allow_zero(true), "0:00", 0),
+            (ParserOptions::new().allow_leading_zeros(true), "05m", 300),
+            (
+                ParserOptions::new().allow_leading_zeros(true),
+                "1h 007s",
+                3607,
+            ),
+            (
+                ParserOptions::new().require_whitespace(true),
+                "1h 30m",
+                5400,
+            ),
+            (
+                ParserOptions::new().require_whitespace(true),
+                "1 h  30 m",
+                5400,
+            ),
+            (ParserOptions::new().strict(true), " 1h 30m ", 5400),
+        ];
+
+        for (options, input, expected) in tests {
+            assert_eq!(
+                options.parse_secs(input).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let tests = &[
+            (
+                ParserOptions::new().enforce_order(false),
+                "1m 1s 1m",
+                Error::AlreadySeen {
+                    span: Span::new(6, 8),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                ParserOptions::new().allow_duplicates(true),
+                "1m 1s 1m",
+                Error::OutOfOrder {
+                    span: Span::new(6, 8),
+                    previous: Span::new(3, 5),
+                },
+            ),
+            (
+                ParserOptions::new().allow_zero(true),
+                "00s",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                ParserOptions::new().allow_leading_zeros(true),
+                "0s",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+            (
+                ParserOptions::new().require_whitespace(true),
+                "1h30m",
+                Error::InvalidData {
+                    span: Span::new(2, 5),
+                },
+            ),
+            (
+                ParserOptions::new().require_whitespace(true),
+                "1h1:00",
+                Error::InvalidData {
+                    span: Span::new(2, 6),
+                },
+            ),
+            (
+                ParserOptions::new().strict(true),
+                "1h, 30m",
+                Error::UnexpectedCharacter {
+                    span: Span::new(2, 3),
+                },
+            ),
+        ];
+
+        for (options, input, expected) in tests {
+            assert_eq!(
+                options.parse_secs(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn default_unit() {
+        let seconds = ParserOptions::new().default_unit(DefaultUnit::Fixed(Magnitude::Second));
+        let millis = ParserOptions::new().default_unit(DefaultUnit::Fixed(Magnitude::Millisecond));
+        let smaller = ParserOptions::new().default_unit(DefaultUnit::NextSmaller);
+        let tests = &[
+            (seconds, "30", 30_000),
+            (seconds, "1m 30", 90_000),
+            (seconds, "1m 30 ", 90_000),
+            (seconds, "1.5", 1_500),
+            (millis, "30", 30),
+            (millis, "1s 30", 1_030),
+            (smaller, "30", 30_000),
+            (smaller, "1m 30", 90_000),
+            (smaller, "1h 30", 90 * 60_000),
+            (smaller, "1d 2h 30", (26 * 60 + 30) * 60_000),
+            (smaller, "1:00 30", 60_030),
+        ];
+
+        for (options, input, expected) in tests {
+            assert_eq!(
+                options.parse_nanos(input).unwrap(),
+                *expected * 1_000_000,
+                "input: {}",
+                input
+            );
+        }
+
+        let tests = &[
+            (
+                seconds,
+                "30 1m",
+                Error::InvalidData {
+                    span: Span::new(0, 2),
+                },
+            ),
+            (
+                seconds,
+                "1s 30",
+                Error::AlreadySeen {
+                    span: Span::new(3, 5),
+                    previous: Span::new(0, 2),
+                },
+            ),
+            (
+                smaller,
+                "1ns 30",
+                Error::InvalidData {
+                    span: Span::new(4, 6),
+                },
+            ),
+            (
+                ParserOptions::new(),
+                "1m 30",
+                Error::InvalidData {
+                    span: Span::new(3, 5),
+                },
+            ),
+        ];
+
+        for (options, input, expected) in tests {
+            assert_eq!(
+                options.parse_secs(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+}
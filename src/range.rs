@@ -0,0 +1,222 @@
+use crate::lexer::{Lexer, TokenKind};
+use crate::{Error, HumanDuration, ParserOptions, Span};
+use std::time::Duration;
+
+/// An inclusive range of durations, e.g. `5m..10m`, see [`parse_range`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DurationRange {
+    min: Duration,
+    max: Duration,
+}
+
+impl DurationRange {
+    /// The range from `min` to `max`, or `None` if `min` is larger
+    pub fn new(min: Duration, max: Duration) -> Option<Self> {
+        match min <= max {
+            true => Some(Self { min, max }),
+            false => None,
+        }
+    }
+
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    pub fn contains(&self, duration: Duration) -> bool {
+        self.min <= duration && duration <= self.max
+    }
+
+    /// A uniformly random duration in the range, e.g. for jittered backoff
+    #[cfg(feature = "rand")]
+    pub fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        use rand::RngExt as _;
+        let nanos = rng.random_range(self.min.as_nanos()..=self.max.as_nanos());
+        let secs = (nanos / crate::NANOS_PER_SEC) as u64;
+        Duration::new(secs, (nanos % crate::NANOS_PER_SEC) as _)
+    }
+}
+
+/// Formats the range as `min..max`, in the canonical form from [`HumanDuration`]
+impl std::fmt::Display for DurationRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}..{}",
+            HumanDuration(self.min),
+            HumanDuration(self.max)
+        )
+    }
+}
+
+impl std::str::FromStr for DurationRange {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_range(input)
+    }
+}
+
+/// Parse a range of durations, `5m..10m`, `5m-10m` or `5m to 10m`
+///
+/// Both ends accept the same format as [`parse_secs_strict`](crate::parse_secs_strict),
+/// and can be zero. A minimum larger than the maximum returns [`Error::OutOfOrder`].
+///
+/// ```rust
+/// use simple_duration_parse::{parse_range, Error, Span};
+/// use std::time::Duration;
+///
+/// let range = parse_range("5m to 10m").unwrap();
+/// assert_eq!(range.min(), Duration::from_secs(300));
+/// assert_eq!(range.max(), Duration::from_secs(600));
+/// assert!(range.contains(Duration::from_secs(450)));
+/// assert_eq!(range.to_string(), "5m..10m");
+///
+/// assert_eq!(
+///     parse_range("10m..5m").unwrap_err(),
+///     Error::OutOfOrder { span: Span::new(5, 7), previous: Span::new(0, 3) }
+/// );
+/// ```
+pub fn parse_range(input: &str) -> Result<DurationRange, Error> {
+    let separator = Lexer::new(input)
+        .find_map(|token| match token.kind {
+            TokenKind::Other('.') if input[token.span.end..].starts_with('.') => {
+                Some(Span::new(token.span.start, token.span.end + 1))
+            }
+            // the first `.` of `1:00..2:00` is lexed as an empty fraction
+            TokenKind::Number {
+                fraction: Some(""), ..
+            } if input[token.span.end..].starts_with('.') => {
+                Some(Span::new(token.span.end - 1, token.span.end + 1))
+            }
+            TokenKind::Other('-') => Some(token.span),
+            TokenKind::Word(word) if word.eq_ignore_ascii_case("to") => Some(token.span),
+            _ => None,
+        })
+        .ok_or(Error::InvalidData {
+            span: Span::new(0, input.len()),
+        })?;
+
+    let (min, previous) = bound(input, Span::new(0, separator.start))?;
+    let (max, span) = bound(input, Span::new(separator.end, input.len()))?;
+    DurationRange::new(min, max).ok_or(Error::OutOfOrder { span, previous })
+}
+
+/// Parse one end of the range, returning it along with its span without whitespace
+fn bound(input: &str, span: Span) -> Result<(Duration, Span), Error> {
+    let bound = &input[span.start..span.end];
+    let duration = ParserOptions::new()
+        .strict(true)
+        .allow_zero(true)
+        .parse(bound)
+        .map_err(|err| err.shift(span.start))?;
+    let start = span.start + bound.len() - bound.trim_start().len();
+    Ok((duration, Span::new(start, start + bound.trim().len())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn range() {
+        let tests = &[
+            ("5m..10m", 300, 600),
+            ("5m-10m", 300, 600),
+            ("5m to 10m", 300, 600),
+            ("5m TO 10m", 300, 600),
+            (" 1m 30s .. 2m ", 90, 120),
+            ("1h30m-2h", 5400, 7200),
+            ("0s..1s", 0, 1),
+            ("1m..1m", 60, 60),
+            ("1:00 - 1:30", 60, 90),
+            ("1:00..2:00", 60, 120),
+            ("1s..1:30", 1, 90),
+        ];
+
+        for (input, min, max) in tests {
+            let expected = DurationRange::new(Duration::from_secs(*min), Duration::from_secs(*max));
+            assert_eq!(parse_range(input).ok(), expected, "input: {}", input);
+        }
+
+        let tests = &[
+            (
+                "",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "5m",
+                Error::InvalidData {
+                    span: Span::new(0, 2),
+                },
+            ),
+            (
+                "5m..",
+                Error::InvalidData {
+                    span: Span::new(4, 4),
+                },
+            ),
+            (
+                "..5m",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "5m .. 1m 1h",
+                Error::OutOfOrder {
+                    span: Span::new(9, 11),
+                    previous: Span::new(6, 8),
+                },
+            ),
+            (
+                " 10m to 5m",
+                Error::OutOfOrder {
+                    span: Span::new(8, 10),
+                    previous: Span::new(1, 4),
+                },
+            ),
+            (
+                "5m, 10m",
+                Error::InvalidData {
+                    span: Span::new(0, 7),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_range(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let range = "90s..1h".parse::<DurationRange>().unwrap();
+        assert_eq!(range.to_string(), "1m 30s..1h");
+        assert_eq!(range.to_string().parse::<DurationRange>().unwrap(), range);
+        assert!(!range.contains(Duration::from_secs(89)));
+        assert!(range.contains(Duration::from_secs(3600)));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sample() {
+        use rand::{rngs::SmallRng, SeedableRng as _};
+
+        let mut rng = SmallRng::seed_from_u64(0);
+        let range = parse_range("1s..2s").unwrap();
+        for _ in 0..1000 {
+            assert!(range.contains(range.sample(&mut rng)));
+        }
+
+        let range = parse_range("1.5s..1.5s").unwrap();
+        assert_eq!(range.sample(&mut rng), Duration::from_millis(1500));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::{Error, ParserOptions, Span, NANOS_PER_SEC};
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime};
+
+/// Which way a [`Relative`] offset points in time
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Past,
+    Future,
+}
+
+/// A signed offset from some instant, see [`parse_relative`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Relative {
+    nanos: i128,
+}
+
+impl Relative {
+    /// The offset in nanoseconds, negative offsets are in the past
+    pub fn nanos(&self) -> i128 {
+        self.nanos
+    }
+
+    pub fn direction(&self) -> Direction {
+        match self.nanos < 0 {
+            true => Direction::Past,
+            false => Direction::Future,
+        }
+    }
+
+    /// Offset the `time` by this amount, or `None` if it can't be represented
+    pub fn apply_to(&self, time: SystemTime) -> Option<SystemTime> {
+        let nanos = self.nanos.unsigned_abs();
+        let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+        let duration = Duration::new(secs, (nanos % NANOS_PER_SEC) as _);
+        match self.direction() {
+            Direction::Past => time.checked_sub(duration),
+            Direction::Future => time.checked_add(duration),
+        }
+    }
+
+    /// Offset the `time` by this amount, or `None` if it's outside of the supported years
+    #[cfg(feature = "time")]
+    pub fn apply_to_offset_date_time(
+        &self,
+        time: time::OffsetDateTime,
+    ) -> Option<time::OffsetDateTime> {
+        checked_add_nanos(time, self.nanos)
+    }
+}
+
+/// Add `nanos` to the `time`, or `None` if it's outside of the supported years
+#[cfg(feature = "time")]
+pub(crate) fn checked_add_nanos(
+    time: time::OffsetDateTime,
+    nanos: i128,
+) -> Option<time::OffsetDateTime> {
+    // adding past the supported range panics, so check it up front
+    let bound = |year, month, day, (h, m, s, ns)| {
+        time::Date::try_from_ymd(year, month, day)
+            .and_then(|date| date.try_with_hms_nano(h, m, s, ns))
+            .map(|date| date.assume_utc().unix_timestamp_nanos())
+    };
+    let min = bound(-100_000, 1, 1, (0, 0, 0, 0)).ok()?;
+    let max = bound(100_000, 12, 31, (23, 59, 59, 999_999_999)).ok()?;
+
+    let target = time.unix_timestamp_nanos().checked_add(nanos)?;
+    if target < min || target > max {
+        return None;
+    }
+
+    let per_sec = NANOS_PER_SEC as i128;
+    let offset = time::Duration::new((nanos / per_sec) as _, (nanos % per_sec) as _);
+    Some(time + offset)
+}
+
+/// Parse a relative phrase, e.g. `in 3h`, `5 minutes from now` or `2 days ago`
+///
+/// The duration between the phrase's words accepts the same format as
+/// [`parse_signed_nanos`](crate::parse_signed_nanos), and only whitespace is
+/// allowed around it. A bare duration is also accepted, where a negative one
+/// is in the past. `ago` negates the duration.
+///
+/// ```rust
+/// use simple_duration_parse::{parse_relative, Direction};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let relative = parse_relative("2 days ago").unwrap();
+/// assert_eq!(relative.direction(), Direction::Past);
+/// assert_eq!(relative.nanos(), -2 * 24 * 60 * 60 * 1_000_000_000);
+///
+/// let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+/// let later = parse_relative("in 3h").unwrap().apply_to(now).unwrap();
+/// assert_eq!(later, now + Duration::from_secs(3 * 60 * 60));
+/// ```
+pub fn parse_relative(input: &str) -> Result<Relative, Error> {
+    let mut tokens = Lexer::new(input).filter(|token| token.kind != TokenKind::Whitespace);
+    let first = tokens.next();
+    let (mut last, mut before) = (first, None);
+    for token in tokens {
+        before = last;
+        last = Some(token);
+    }
+
+    let word = |token: Option<Token<'_>>, expected: &str| match token {
+        Some(Token {
+            kind: TokenKind::Word(word),
+            span,
+        }) if word.eq_ignore_ascii_case(expected) => Some(span),
+        _ => None,
+    };
+
+    let (mut start, mut end) = (0, input.len());
+    let mut phrase = None;
+    if let Some(span) = word(first, "in") {
+        start = span.end;
+        phrase.replace(Direction::Future);
+    }
+
+    let suffix = match (word(before, "from"), word(last, "now"), word(last, "ago")) {
+        (Some(from), Some(now), _) => Some((Direction::Future, Span::new(from.start, now.end))),
+        (_, _, Some(ago)) => Some((Direction::Past, ago)),
+        _ => None,
+    };
+    if let Some((direction, span)) = suffix {
+        if phrase.is_some() {
+            return Err(Error::InvalidData { span });
+        }
+        end = span.start;
+        phrase.replace(direction);
+    }
+
+    let nanos = crate::parse(&input[start..end], &ParserOptions::new().strict(true))
+        .map_err(|err| err.shift(start))?;
+    let nanos = match phrase {
+        Some(Direction::Past) => nanos.checked_neg().ok_or(crate::overflow(input))?,
+        _ => nanos,
+    };
+    Ok(Relative { nanos })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative() {
+        const MIN: i128 = 60 * NANOS_PER_SEC as i128;
+        let tests = &[
+            ("in 3h", 180 * MIN),
+            ("IN 3 hours", 180 * MIN),
+            ("5 minutes from now", 5 * MIN),
+            ("5m From  Now", 5 * MIN),
+            ("2 days ago", -2 * 24 * 60 * MIN),
+            ("1h 30m ago", -90 * MIN),
+            ("  in 1:30:00  ", 90 * MIN),
+            ("in -5m", -5 * MIN),
+            ("-5m ago", 5 * MIN),
+            ("5m", 5 * MIN),
+            ("-5m", -5 * MIN),
+        ];
+
+        for (input, expected) in tests {
+            let relative = parse_relative(input).unwrap();
+            assert_eq!(relative.nanos(), *expected, "input: {}", input);
+            let direction = match *expected < 0 {
+                true => Direction::Past,
+                false => Direction::Future,
+            };
+            assert_eq!(relative.direction(), direction, "input: {}", input);
+        }
+
+        let tests = &[
+            (
+                "in",
+                Error::InvalidData {
+                    span: Span::new(2, 2),
+                },
+            ),
+            (
+                "ago",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "in 5m ago",
+                Error::InvalidData {
+                    span: Span::new(6, 9),
+                },
+            ),
+            (
+                "in 5m from now",
+                Error::InvalidData {
+                    span: Span::new(6, 14),
+                },
+            ),
+            (
+                "in 5m, please",
+                Error::UnexpectedCharacter {
+                    span: Span::new(5, 6),
+                },
+            ),
+            (
+                "in 5m 1h",
+                Error::OutOfOrder {
+                    span: Span::new(6, 8),
+                    previous: Span::new(3, 5),
+                },
+            ),
+            (
+                "5 from now",
+                Error::InvalidData {
+                    span: Span::new(0, 1),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_relative(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn apply() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let tests = &[
+            ("in 1m", now + Duration::from_secs(60)),
+            ("1m ago", now - Duration::from_secs(60)),
+            ("1500ms ago", now - Duration::from_millis(1500)),
+        ];
+
+        for (input, expected) in tests {
+            let relative = parse_relative(input).unwrap();
+            assert_eq!(
+                relative.apply_to(now).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let huge = parse_relative("in 1000000000000000000000y").unwrap();
+        assert_eq!(huge.apply_to(now), None);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn apply_offset_date_time() {
+        let now = time::OffsetDateTime::from_unix_timestamp(1_000_000);
+        let tests = &[
+            ("in 1m", now + time::Duration::minutes(1)),
+            ("1m ago", now - time::Duration::minutes(1)),
+            (
+                "2d 1ms ago",
+                now - time::Duration::milliseconds(2 * 86_400_000 + 1),
+            ),
+        ];
+
+        for (input, expected) in tests {
+            let relative = parse_relative(input).unwrap();
+            assert_eq!(
+                relative.apply_to_offset_date_time(now).unwrap(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        for input in &["in 100000y", "200000y ago", "in 1000000000000000000000y"] {
+            let relative = parse_relative(input).unwrap();
+            assert_eq!(
+                relative.apply_to_offset_date_time(now),
+                None,
+                "input: {}",
+                input
+            );
+        }
+    }
+}
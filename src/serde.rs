@@ -0,0 +1,277 @@
+//! Serde helpers for [`Duration`] fields
+//!
+//! Durations deserialize from either a human-readable string, parsed
+//! [strictly](crate::ParserOptions::strict) so that stray text is an error, or
+//! an integer number of seconds. They serialize into the canonical form from
+//! [`HumanDuration`]. Zero durations are allowed, so `0s` round-trips.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     #[serde(with = "simple_duration_parse::serde")]
+//!     timeout: Duration,
+//!     #[serde(with = "simple_duration_parse::serde::option", default)]
+//!     retry: Option<Duration>,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{ "timeout": "1m 30s" }"#).unwrap();
+//! assert_eq!(config.timeout, Duration::from_secs(90));
+//! assert_eq!(config.retry, None);
+//!
+//! let config: Config = serde_json::from_str(r#"{ "timeout": 90, "retry": "5s" }"#).unwrap();
+//! assert_eq!(
+//!     serde_json::to_string(&config).unwrap(),
+//!     r#"{"timeout":"1m 30s","retry":"5s"}"#
+//! );
+//! ```
+
+use crate::{HumanDuration, ParserOptions};
+use ::serde::de::{self, Deserializer, Visitor};
+use ::serde::ser::Serializer;
+use std::fmt;
+use std::time::Duration;
+
+/// Serialize the duration in its canonical human-readable form
+pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&HumanDuration(*duration))
+}
+
+/// Deserialize a duration from a human-readable string or integer seconds
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    deserializer.deserialize_any(DurationVisitor)
+}
+
+struct DurationVisitor;
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a duration like \"1h 30m\" or a number of seconds")
+    }
+
+    fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Self::Value, E> {
+        Ok(Duration::from_secs(secs))
+    }
+
+    fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Self::Value, E> {
+        match secs < 0 {
+            true => Err(E::invalid_value(de::Unexpected::Signed(secs), &self)),
+            false => Ok(Duration::from_secs(secs as u64)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, input: &str) -> Result<Self::Value, E> {
+        ParserOptions::new()
+            .strict(true)
+            .allow_zero(true)
+            .parse(input)
+            .map_err(|err| E::custom(format_args!("invalid duration {:?}: {}", input, err)))
+    }
+}
+
+/// Serde helpers for `Option<Duration>` fields, see the [module](self) docs
+pub mod option {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_some(&HumanDuration(*duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor)
+    }
+
+    struct OptionVisitor;
+
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<Duration>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            DurationVisitor.expecting(f)?;
+            f.write_str(", or nothing")
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> Result<Self::Value, D::Error> {
+            super::deserialize(deserializer).map(Some)
+        }
+    }
+}
+
+/// Serde helpers for `Vec<Duration>` fields, see the [module](self) docs
+pub mod vec {
+    use super::*;
+    use ::serde::de::SeqAccess;
+    use ::serde::ser::SerializeSeq;
+
+    pub fn serialize<S: Serializer>(
+        durations: &[Duration],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(durations.len()))?;
+        for duration in durations {
+            seq.serialize_element(&HumanDuration(*duration))?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Duration>, D::Error> {
+        deserializer.deserialize_seq(VecVisitor)
+    }
+
+    struct VecVisitor;
+
+    impl<'de> Visitor<'de> for VecVisitor {
+        type Value = Vec<Duration>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a list of durations")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut durations = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+            while let Some(HumanDuration(duration)) = seq.next_element()? {
+                durations.push(duration);
+            }
+            Ok(durations)
+        }
+    }
+}
+
+impl ::serde::Serialize for HumanDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de> ::serde::Deserialize<'de> for HumanDuration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(HumanDuration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HumanDuration;
+    use ::serde::{Deserialize, Serialize};
+    use std::time::Duration;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        #[serde(with = "crate::serde")]
+        timeout: Duration,
+        #[serde(with = "crate::serde::option", default)]
+        retry: Option<Duration>,
+        #[serde(with = "crate::serde::vec", default)]
+        backoff: Vec<Duration>,
+    }
+
+    #[test]
+    fn deserialize() {
+        let tests = &[
+            (r#"{"timeout": "1m 30s"}"#, 90, None, vec![]),
+            (r#"{"timeout": 90}"#, 90, None, vec![]),
+            (r#"{"timeout": "0s", "retry": null}"#, 0, None, vec![]),
+            (r#"{"timeout": 0, "retry": "2s"}"#, 0, Some(2), vec![]),
+            (
+                r#"{"timeout": "1h", "retry": 5, "backoff": ["1s", 2, "1m"]}"#,
+                3600,
+                Some(5),
+                vec![1, 2, 60],
+            ),
+        ];
+
+        for (input, timeout, retry, backoff) in tests {
+            let expected = Config {
+                timeout: Duration::from_secs(*timeout),
+                retry: retry.map(Duration::from_secs),
+                backoff: backoff.iter().copied().map(Duration::from_secs).collect(),
+            };
+            assert_eq!(
+                serde_json::from_str::<Config>(input).unwrap(),
+                expected,
+                "input: {}",
+                input
+            );
+        }
+
+        assert_eq!(
+            serde_json::from_str::<Config>(r#"{"timeout": "1m 1h"}"#)
+                .unwrap_err()
+                .to_string(),
+            "invalid duration \"1m 1h\": Out of order at 3..5 (after 0..2) at line 1 column 19"
+        );
+
+        let tests = &[
+            r#"{"timeout": -1}"#,
+            r#"{"timeout": 1.5}"#,
+            r#"{"timeout": true}"#,
+            r#"{"timeout": "foobar"}"#,
+            r#"{"timeout": ""}"#,
+            r#"{"timeout": "1m", "backoff": "1s"}"#,
+            r#"{"timeout": "1m", "backoff": ["-1s"]}"#,
+        ];
+
+        for input in tests {
+            assert!(
+                serde_json::from_str::<Config>(input).is_err(),
+                "input: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn human_duration() {
+        let durations: Vec<HumanDuration> = serde_json::from_str(r#"["1m", 5, "0s"]"#).unwrap();
+        let expected = &[60, 5, 0];
+        assert_eq!(durations.len(), expected.len());
+        for (duration, secs) in durations.iter().zip(expected) {
+            assert_eq!(duration.as_secs(), *secs);
+        }
+        assert_eq!(
+            serde_json::to_string(&durations).unwrap(),
+            r#"["1m","5s","0s"]"#
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let config = Config {
+            timeout: Duration::new(3661, 500_000_000),
+            retry: Some(Duration::from_secs(0)),
+            backoff: vec![Duration::from_millis(1), Duration::from_secs(60)],
+        };
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(
+            json,
+            r#"{"timeout":"1h 1m 1s 500ms","retry":"0s","backoff":["1ms","1m"]}"#
+        );
+        assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+    }
+}
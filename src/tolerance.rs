@@ -0,0 +1,269 @@
+use crate::lexer::{Lexer, TokenKind};
+use crate::{DurationParser, DurationRange, Error, HumanDuration, ParserOptions, Span};
+use std::time::Duration;
+
+/// A nominal duration with an allowed deviation either way, see [`parse_tolerance`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tolerance {
+    nominal: Duration,
+    tolerance: Duration,
+}
+
+impl Tolerance {
+    /// The nominal duration give or take `tolerance`, or `None` if the maximum overflows
+    pub fn new(nominal: Duration, tolerance: Duration) -> Option<Self> {
+        nominal.checked_add(tolerance)?;
+        Some(Self { nominal, tolerance })
+    }
+
+    pub fn nominal(&self) -> Duration {
+        self.nominal
+    }
+
+    /// The allowed deviation, percentages are already applied to the nominal duration
+    pub fn tolerance(&self) -> Duration {
+        self.tolerance
+    }
+
+    /// The smallest allowed duration, which stops at zero
+    pub fn min(&self) -> Duration {
+        self.nominal.saturating_sub(self.tolerance)
+    }
+
+    pub fn max(&self) -> Duration {
+        self.nominal + self.tolerance
+    }
+
+    pub fn contains(&self, duration: Duration) -> bool {
+        self.range().contains(duration)
+    }
+
+    /// The durations from [`min`](Self::min) to [`max`](Self::max)
+    pub fn range(&self) -> DurationRange {
+        DurationRange::new(self.min(), self.max()).expect("min is never larger than max")
+    }
+}
+
+/// Formats the tolerance as `nominal ± tolerance`, in the canonical form from [`HumanDuration`]
+impl std::fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ± {}",
+            HumanDuration(self.nominal),
+            HumanDuration(self.tolerance)
+        )
+    }
+}
+
+impl std::str::FromStr for Tolerance {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        parse_tolerance(input)
+    }
+}
+
+/// Parse a nominal duration with a tolerance, `5m ± 30s` or `5m +/- 10%`
+///
+/// Both durations accept the same format as [`parse_secs_strict`](crate::parse_secs_strict),
+/// and can be zero. A percentage has to give whole nanoseconds of the nominal
+/// duration, otherwise it returns [`Error::Precision`].
+///
+/// ```rust
+/// use simple_duration_parse::parse_tolerance;
+/// use std::time::Duration;
+///
+/// let tolerance = parse_tolerance("5m +/- 10%").unwrap();
+/// assert_eq!(tolerance.nominal(), Duration::from_secs(300));
+/// assert_eq!(tolerance.min(), Duration::from_secs(270));
+/// assert_eq!(tolerance.max(), Duration::from_secs(330));
+/// assert!(tolerance.contains(Duration::from_secs(280)));
+/// assert!(!tolerance.contains(Duration::from_secs(331)));
+/// assert_eq!(tolerance.to_string(), "5m ± 30s");
+/// ```
+pub fn parse_tolerance(input: &str) -> Result<Tolerance, Error> {
+    let separator = Lexer::new(input)
+        .find_map(|token| match token.kind {
+            TokenKind::Other('±') => Some(token.span),
+            TokenKind::Other('+') if input[token.span.end..].starts_with("/-") => {
+                Some(Span::new(token.span.start, token.span.end + 2))
+            }
+            _ => None,
+        })
+        .ok_or(Error::InvalidData {
+            span: Span::new(0, input.len()),
+        })?;
+
+    let options = ParserOptions::new().strict(true).allow_zero(true);
+    let nominal: Duration = options.parse(&input[..separator.start])?;
+
+    let span = Span::new(separator.end, input.len());
+    let tolerance = match input[span.start..].trim_end().strip_suffix('%') {
+        Some(percent) => {
+            let start = span.start + percent.len() - percent.trim_start().len();
+            percentage(
+                nominal,
+                percent.trim(),
+                Span::new(start, start + percent.trim().len() + 1),
+            )?
+        }
+        None => options
+            .parse(&input[span.start..])
+            .map_err(|err| err.shift(span.start))?,
+    };
+    Tolerance::new(nominal, tolerance).ok_or(Error::Overflow {
+        span: Span::new(0, input.len()),
+    })
+}
+
+/// `percent`% of the `nominal` duration, where `span` covers the percentage
+fn percentage(nominal: Duration, percent: &str, span: Span) -> Result<Duration, Error> {
+    let invalid = Error::InvalidData { span };
+    let (whole, fraction) = match percent.find('.') {
+        Some(dot) => (&percent[..dot], &percent[dot + 1..]),
+        None => (percent, ""),
+    };
+    let digits = |s: &str| s.bytes().all(|c| c.is_ascii_digit());
+    if whole.is_empty() || !digits(whole) || !digits(fraction) || percent.ends_with('.') {
+        return Err(invalid);
+    }
+
+    let overflow = Error::Overflow { span };
+    let mut num: u128 = 0;
+    for digit in whole.bytes().chain(fraction.bytes()) {
+        num = num
+            .checked_mul(10)
+            .and_then(|num| num.checked_add((digit - b'0') as u128))
+            .ok_or(overflow)?;
+    }
+    let den = 10_u128
+        .checked_pow(fraction.len() as u32)
+        .and_then(|den| den.checked_mul(100))
+        .ok_or(overflow)?;
+
+    let nanos = nominal.as_nanos().checked_mul(num).ok_or(overflow)?;
+    if nanos % den != 0 {
+        return Err(Error::Precision { span });
+    }
+    <Duration as DurationParser>::from_nanos((nanos / den) as i128, span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tolerance() {
+        const MS: u64 = 1_000_000;
+
+        let tests = &[
+            ("5m ± 30s", 300_000, 270_000, 330_000),
+            ("5m±30s", 300_000, 270_000, 330_000),
+            ("5m +/- 10%", 300_000, 270_000, 330_000),
+            ("5m+/-10 %", 300_000, 270_000, 330_000),
+            ("1s ± 2.5%", 1_000, 975, 1_025),
+            ("1s ± 0%", 1_000, 1_000, 1_000),
+            ("1s ± 150%", 1_000, 0, 2_500),
+            ("1s ± 2s", 1_000, 0, 3_000),
+            ("1m 30s ± 1:00", 90_000, 30_000, 150_000),
+        ];
+
+        for (input, nominal, min, max) in tests {
+            let tolerance = parse_tolerance(input).unwrap();
+            assert_eq!(
+                tolerance.nominal(),
+                Duration::from_nanos(nominal * MS),
+                "input: {}",
+                input
+            );
+            assert_eq!(
+                tolerance.min(),
+                Duration::from_nanos(min * MS),
+                "input: {}",
+                input
+            );
+            assert_eq!(
+                tolerance.max(),
+                Duration::from_nanos(max * MS),
+                "input: {}",
+                input
+            );
+        }
+
+        let tests = &[
+            (
+                "5m",
+                Error::InvalidData {
+                    span: Span::new(0, 2),
+                },
+            ),
+            (
+                "5m ± ",
+                Error::InvalidData {
+                    span: Span::new(5, 6),
+                },
+            ),
+            (
+                "± 30s",
+                Error::InvalidData {
+                    span: Span::new(0, 0),
+                },
+            ),
+            (
+                "5m ± %",
+                Error::InvalidData {
+                    span: Span::new(6, 7),
+                },
+            ),
+            (
+                "5m ± 1.%",
+                Error::InvalidData {
+                    span: Span::new(6, 9),
+                },
+            ),
+            (
+                "5m ± 1s%",
+                Error::InvalidData {
+                    span: Span::new(6, 9),
+                },
+            ),
+            (
+                "1ns ± 10%",
+                Error::Precision {
+                    span: Span::new(7, 10),
+                },
+            ),
+            (
+                "5m ± 1m 1h",
+                Error::OutOfOrder {
+                    span: Span::new(9, 11),
+                    previous: Span::new(6, 8),
+                },
+            ),
+            (
+                "5m +- 30s",
+                Error::InvalidData {
+                    span: Span::new(0, 9),
+                },
+            ),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                parse_tolerance(input).unwrap_err(),
+                *expected,
+                "input: {}",
+                input
+            );
+        }
+
+        let tolerance = "90s ± 5%".parse::<Tolerance>().unwrap();
+        assert_eq!(tolerance.to_string(), "1m 30s ± 4s 500ms");
+        assert_eq!(
+            tolerance.to_string().parse::<Tolerance>().unwrap(),
+            tolerance
+        );
+        assert_eq!(tolerance.range(), "85s 500ms..94s 500ms".parse().unwrap());
+    }
+}
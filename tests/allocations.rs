@@ -0,0 +1,79 @@
+use simple_duration_parse::{
+    find_durations, format_secs, parse_iso8601_nanos, parse_nanos, parse_relative, parse_secs,
+    parse_secs_strict, parse_signed_nanos, DefaultUnit, ParserOptions,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the allocations made on the current thread
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn parsing_does_not_allocate() {
+    // make sure allocations are actually counted
+    assert!(allocations(|| drop(format_secs(60))) > 0);
+
+    let inputs = &[
+        "1h 1m 1s",
+        "3 hours 5 minutes",
+        "1.5h 250ms",
+        "1:02:03.5",
+        "2d 03:00:00",
+        "-1h +30m",
+        "1s foobar",
+        "3µs",
+        "1s 1m",
+        "06s",
+        "1:60",
+        "",
+    ];
+
+    let options = ParserOptions::new()
+        .enforce_order(false)
+        .allow_zero(true)
+        .default_unit(DefaultUnit::NextSmaller);
+
+    for input in inputs {
+        let count = allocations(|| {
+            let _ = parse_secs(input);
+            let _ = parse_secs_strict(input);
+            let _ = parse_nanos(input);
+            let _ = parse_signed_nanos(input);
+            let _ = options.parse_nanos(input);
+            let _ = parse_relative(input);
+            find_durations(input).for_each(drop);
+        });
+        assert_eq!(count, 0, "input: {}", input);
+    }
+
+    for input in &["P3DT4H30M", "PT1.5S", "P1Y"] {
+        let count = allocations(|| {
+            let _ = parse_iso8601_nanos(input);
+        });
+        assert_eq!(count, 0, "input: {}", input);
+    }
+}